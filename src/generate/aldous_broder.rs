@@ -0,0 +1,24 @@
+use super::{Grid, Rng};
+
+// The Aldous-Broder algorithm walks randomly through the grid, and links each cell it enters for the first time to
+// the cell it came from. It picks uniformly among all possible mazes, but it is slow, as the walk keeps going back
+// through the maze to find the last few cells.
+pub(super) fn carve<R: Rng>(grid: &mut Grid, rng: &mut R) {
+    let cells = grid.cells();
+    let mut visited = vec![false; cells.len()];
+    let mut current = cells[rng.below(cells.len())];
+    visited[grid.index(current)] = true;
+    let mut remaining = cells.len() - 1;
+
+    while remaining > 0 {
+        let Some(next) = rng.choose(&grid.neighbours(current)).copied() else {
+            break;
+        };
+        if !visited[grid.index(next)] {
+            visited[grid.index(next)] = true;
+            grid.link(current, next);
+            remaining -= 1;
+        }
+        current = next;
+    }
+}
@@ -0,0 +1,16 @@
+use super::{Grid, Rng};
+
+// The binary tree algorithm links each cell to its neighbour above or on the left, picked at random. It needs no
+// memory at all, but the top row and left column are always straight corridors, and every path towards the top-left
+// corner only goes up and left.
+pub(super) fn carve<R: Rng>(grid: &mut Grid, rng: &mut R) {
+    for (x, y) in grid.cells() {
+        let neighbours: Vec<_> = [(y > 0).then(|| (x, y - 1)), (x > 0).then(|| (x - 1, y))]
+            .into_iter()
+            .flatten()
+            .collect();
+        if let Some(neighbour) = rng.choose(&neighbours).copied() {
+            grid.link((x, y), neighbour);
+        }
+    }
+}
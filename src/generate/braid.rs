@@ -0,0 +1,204 @@
+use crate::tilemap::{Tile, TileMap};
+
+use super::{GenerateError, Rng};
+
+// The directions a wall is knocked down in, as offsets
+const DIRECTIONS: [(isize, isize); 4] = [(0, -1), (-1, 0), (0, 1), (1, 0)];
+
+// Braids the maze: removes the given fraction (between 0 and 1) of its dead ends by knocking down one of their walls,
+// which joins them to the corridor behind that wall and creates a loop. When the corridor behind a wall is a dead end
+// too, that wall is preferred, as knocking it down removes both dead ends at once.
+// Dead ends are the walkable tiles other than the start and end tiles with exactly one walkable neighbour (up, left,
+// down or right). This works on any tilemap, not only generated ones, but a dead end can only be removed if one of its
+// walls is a single tile thick and inside the outer walls, so the fraction removed can be lower on other tilemaps.
+pub fn braid<R: Rng>(
+    tilemap: &TileMap,
+    fraction: f64,
+    rng: &mut R,
+) -> Result<TileMap, GenerateError> {
+    if !(0. ..=1.).contains(&fraction) {
+        return Err(GenerateError::InvalidFraction(fraction));
+    }
+
+    let mut tilemap = tilemap.clone();
+    let mut dead_ends: Vec<(usize, usize)> = (0..tilemap.height())
+        .flat_map(|y| (0..tilemap.width()).map(move |x| (x, y)))
+        .filter(|coord| is_dead_end(&tilemap, *coord))
+        .collect();
+    let target = (dead_ends.len() as f64 * fraction).round() as usize;
+    rng.shuffle(&mut dead_ends);
+
+    let mut removed = 0;
+    for dead_end in dead_ends {
+        if removed >= target {
+            break;
+        }
+        // The dead end may have been removed already, along with a neighbouring one
+        if !is_dead_end(&tilemap, dead_end) {
+            continue;
+        }
+
+        let walls = removable_walls(&tilemap, dead_end);
+        let joining_dead_ends: Vec<_> = walls
+            .iter()
+            .copied()
+            .filter(|(_, behind)| is_dead_end(&tilemap, *behind))
+            .collect();
+        let candidates = if joining_dead_ends.is_empty() {
+            walls
+        } else {
+            joining_dead_ends
+        };
+        let Some(((x, y), behind)) = rng.choose(&candidates).copied() else {
+            continue;
+        };
+
+        removed += if is_dead_end(&tilemap, behind) { 2 } else { 1 };
+        tilemap.set(x, y, Tile::Empty);
+    }
+
+    Ok(tilemap)
+}
+
+fn is_walkable(tilemap: &TileMap, (x, y): (usize, usize)) -> bool {
+    tilemap
+        .get(x, y)
+        .is_some_and(|tile| tile.weight().is_some())
+}
+
+// The tile at the given offset from a coordinate, if it is inside the tilemap
+fn offset(
+    tilemap: &TileMap,
+    (x, y): (usize, usize),
+    (dx, dy): (isize, isize),
+) -> Option<(usize, usize)> {
+    let x = x.checked_add_signed(dx).filter(|x| *x < tilemap.width())?;
+    let y = y.checked_add_signed(dy).filter(|y| *y < tilemap.height())?;
+    Some((x, y))
+}
+
+fn open_neighbours(tilemap: &TileMap, coord: (usize, usize)) -> usize {
+    DIRECTIONS
+        .iter()
+        .filter_map(|direction| offset(tilemap, coord, *direction))
+        .filter(|neighbour| is_walkable(tilemap, *neighbour))
+        .count()
+}
+
+fn is_dead_end(tilemap: &TileMap, (x, y): (usize, usize)) -> bool {
+    matches!(
+        tilemap.get(x, y),
+        Some(Tile::Empty) | Some(Tile::Mud) | Some(Tile::Water)
+    ) && open_neighbours(tilemap, (x, y)) == 1
+}
+
+// The walls around a dead end that can be knocked down, each with the walkable tile behind it. The outer walls are
+// never knocked down, so that the maze stays closed.
+fn removable_walls(
+    tilemap: &TileMap,
+    dead_end: (usize, usize),
+) -> Vec<((usize, usize), (usize, usize))> {
+    DIRECTIONS
+        .iter()
+        .filter_map(|(dx, dy)| {
+            let wall = offset(tilemap, dead_end, (*dx, *dy))?;
+            let behind = offset(tilemap, dead_end, (dx * 2, dy * 2))?;
+            let inside = (1..tilemap.width() - 1).contains(&wall.0)
+                && (1..tilemap.height() - 1).contains(&wall.1);
+            (inside
+                && tilemap.get(wall.0, wall.1) == Some(Tile::Wall)
+                && is_walkable(tilemap, behind))
+            .then_some((wall, behind))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, rc::Rc};
+
+    use super::{braid, is_dead_end, removable_walls};
+    use crate::generate::{generate, Algorithm, GenerateError, Placement, SplitMix64};
+    use crate::maze::Maze;
+    use crate::search::bfs::BreadthFirstSearcher;
+    use crate::test_utils::{load_example, solve, EXAMPLES};
+    use crate::tilemap::{Tile, TileMap};
+
+    // Generated mazes with many dead ends, followed by the bundled examples
+    fn mazes() -> Result<Vec<TileMap>, Box<dyn Error>> {
+        let mut mazes = Vec::new();
+        for seed in [1, 2, 3] {
+            mazes.push(generate(Algorithm::Prim, 41, 41, Placement::Border, seed)?);
+        }
+        for example in EXAMPLES {
+            mazes.push(load_example(example)?);
+        }
+        Ok(mazes)
+    }
+
+    fn removable_dead_ends(tilemap: &TileMap) -> usize {
+        (0..tilemap.height())
+            .flat_map(|y| (0..tilemap.width()).map(move |x| (x, y)))
+            .filter(|coord| {
+                is_dead_end(tilemap, *coord) && !removable_walls(tilemap, *coord).is_empty()
+            })
+            .count()
+    }
+
+    fn is_solvable(tilemap: TileMap) -> Result<bool, Box<dyn Error>> {
+        let maze = Rc::new(Maze::try_from(tilemap)?);
+        Ok(solve(&mut BreadthFirstSearcher::new(&maze)).is_some())
+    }
+
+    #[test]
+    fn fraction_0_leaves_the_maze_unchanged() -> Result<(), Box<dyn Error>> {
+        for tilemap in mazes()? {
+            assert_eq!(braid(&tilemap, 0., &mut SplitMix64::new(7))?, tilemap);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn fraction_1_leaves_no_removable_dead_ends() -> Result<(), Box<dyn Error>> {
+        for tilemap in mazes()? {
+            assert!(removable_dead_ends(&tilemap) > 0);
+            let braided = braid(&tilemap, 1., &mut SplitMix64::new(7))?;
+            assert_eq!(removable_dead_ends(&braided), 0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn braided_mazes_stay_solvable() -> Result<(), Box<dyn Error>> {
+        for tilemap in mazes()? {
+            for fraction in [0.25, 0.5, 1.] {
+                let braided = braid(&tilemap, fraction, &mut SplitMix64::new(7))?;
+                assert!(removable_dead_ends(&braided) <= removable_dead_ends(&tilemap));
+                // Only walls are knocked down, and only into empty tiles
+                for y in 0..tilemap.height() {
+                    for x in 0..tilemap.width() {
+                        let (before, after) = (tilemap.get(x, y), braided.get(x, y));
+                        assert!(
+                            before == after
+                                || (before, after) == (Some(Tile::Wall), Some(Tile::Empty))
+                        );
+                    }
+                }
+                assert!(is_solvable(braided)?);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_fractions_are_rejected() -> Result<(), Box<dyn Error>> {
+        let tilemap = generate(Algorithm::Prim, 11, 11, Placement::Border, 1)?;
+        for fraction in [-0.1, 1.5, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                braid(&tilemap, fraction, &mut SplitMix64::new(7)),
+                Err(GenerateError::InvalidFraction(_))
+            ));
+        }
+        Ok(())
+    }
+}
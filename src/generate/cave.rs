@@ -0,0 +1,388 @@
+use std::collections::VecDeque;
+
+use crate::tilemap::{Metadata, Tile, TileMap};
+
+use super::{GenerateError, Rng, SplitMix64};
+
+// The number of times a cave is generated again when it starts with fewer than two empty tiles, which only happens
+// with high fill ratios
+const CAVE_ATTEMPTS: usize = 100;
+
+// The offsets of the neighbours of a tile, the first four being its orthogonal neighbours
+const NEIGHBOURS: [(isize, isize); 8] = [
+    (0, -1),
+    (-1, 0),
+    (0, 1),
+    (1, 0),
+    (-1, -1),
+    (1, -1),
+    (-1, 1),
+    (1, 1),
+];
+
+// The parameters of the cellular automaton that shapes the caves
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CaveOptions {
+    // The probability of each tile to start as a wall, at least 0 and below 1 (a ratio of 1 leaves no empty tile).
+    // Around 0.45 gives large open caves, and higher ratios give narrower and more broken ones.
+    pub fill_ratio: f64,
+    // The number of smoothing steps, each one turning the tiles surrounded by walls into walls and the others into
+    // empty tiles. Without smoothing, the cave is just noise.
+    pub smoothing: usize,
+}
+
+impl Default for CaveOptions {
+    fn default() -> Self {
+        CaveOptions {
+            fill_ratio: 0.45,
+            smoothing: 4,
+        }
+    }
+}
+
+impl CaveOptions {
+    // The metadata saved along with a generated cave, so that it can be generated again
+    pub fn metadata(&self, seed: u64) -> Metadata {
+        let mut metadata = Metadata::new();
+        metadata.insert("algorithm", "cave");
+        metadata.insert("fill-ratio", &self.fill_ratio.to_string());
+        metadata.insert("smoothing", &self.smoothing.to_string());
+        metadata.insert("seed", &seed.to_string());
+        metadata
+    }
+}
+
+// Generates an open, cave-like map of the given size in tiles (including the outer walls) with a cellular automaton.
+// The tiles start as walls or empty tiles at random, and are then smoothed so that they clump together into caves.
+// The caves left apart from the largest one are joined to it by digging the shortest tunnels, so every empty tile is
+// reachable, even without diagonal moves. The start and end tiles are placed in the empty tiles the closest to the
+// top-left and bottom-right corners.
+pub fn cave(
+    width: usize,
+    height: usize,
+    options: CaveOptions,
+    seed: u64,
+) -> Result<TileMap, GenerateError> {
+    cave_with(width, height, options, &mut SplitMix64::new(seed))
+}
+
+// Generates a cave like `cave`, drawing the random numbers from the given generator
+pub fn cave_with<R: Rng>(
+    width: usize,
+    height: usize,
+    options: CaveOptions,
+    rng: &mut R,
+) -> Result<TileMap, GenerateError> {
+    if !(0. ..1.).contains(&options.fill_ratio) {
+        return Err(GenerateError::InvalidFillRatio(options.fill_ratio));
+    }
+    // Caves have the same minimum size as mazes
+    if width.saturating_sub(1) / 2 * (height.saturating_sub(1) / 2) < 2 {
+        return Err(GenerateError::CaveTooSmall { width, height });
+    }
+
+    for _ in 0..CAVE_ATTEMPTS {
+        let mut cave = Cave::random(width, height, options.fill_ratio, rng);
+        for _ in 0..options.smoothing {
+            if !cave.smooth() {
+                break;
+            }
+        }
+        if cave.open_tiles().len() >= 2 {
+            cave.connect();
+            return cave.into_tilemap();
+        }
+    }
+    Err(GenerateError::EmptyCave {
+        attempts: CAVE_ATTEMPTS,
+    })
+}
+
+// The tiles of the cave being generated, true for the empty ones. The outer tiles always stay walls.
+struct Cave {
+    width: usize,
+    height: usize,
+    open: Vec<Vec<bool>>,
+    // The tiles before the last smoothing step
+    previous: Vec<Vec<bool>>,
+}
+
+impl Cave {
+    fn random<R: Rng>(width: usize, height: usize, fill_ratio: f64, rng: &mut R) -> Cave {
+        let mut cave = Cave {
+            width,
+            height,
+            open: vec![vec![false; width]; height],
+            previous: Vec::new(),
+        };
+        for (x, y) in cave.inner_tiles() {
+            cave.open[y][x] = !rng.chance(fill_ratio);
+        }
+        cave
+    }
+
+    // The tiles inside the outer walls
+    fn inner_tiles(&self) -> Vec<(usize, usize)> {
+        (1..self.height - 1)
+            .flat_map(|y| (1..self.width - 1).map(move |x| (x, y)))
+            .collect()
+    }
+
+    fn open_tiles(&self) -> Vec<(usize, usize)> {
+        self.inner_tiles()
+            .into_iter()
+            .filter(|(x, y)| self.open[*y][*x])
+            .collect()
+    }
+
+    // The neighbours of a tile at the given offsets that are inside the cave
+    fn neighbours(
+        &self,
+        (x, y): (usize, usize),
+        offsets: &[(isize, isize)],
+    ) -> Vec<(usize, usize)> {
+        offsets
+            .iter()
+            .filter_map(|(dx, dy)| {
+                let x = x.checked_add_signed(*dx).filter(|x| *x < self.width)?;
+                let y = y.checked_add_signed(*dy).filter(|y| *y < self.height)?;
+                Some((x, y))
+            })
+            .collect()
+    }
+
+    fn is_inner(&self, (x, y): (usize, usize)) -> bool {
+        (1..self.width - 1).contains(&x) && (1..self.height - 1).contains(&y)
+    }
+
+    // One step of the automaton: a tile with more than 4 walls around it becomes a wall, a tile with less than 4
+    // becomes empty, and a tile with exactly 4 stays as it is. Tiles outside the cave count as walls, so small caves
+    // can close up entirely: the step is only applied (and true returned) if it leaves at least two empty tiles.
+    // The automaton ends up either stable or alternating between two states, after which more steps change nothing
+    // worth the time, so the step is not applied either if it gives the tiles back as they are or as they were
+    // before the last step.
+    fn smooth(&mut self) -> bool {
+        let mut open = self.open.clone();
+        for (x, y) in self.inner_tiles() {
+            let open_neighbours = self
+                .neighbours((x, y), &NEIGHBOURS)
+                .into_iter()
+                .filter(|(x, y)| self.open[*y][*x])
+                .count();
+            match 8 - open_neighbours {
+                walls if walls > 4 => open[y][x] = false,
+                walls if walls < 4 => open[y][x] = true,
+                _ => {}
+            }
+        }
+        if open == self.open
+            || open == self.previous
+            || open.iter().flatten().filter(|open| **open).count() < 2
+        {
+            return false;
+        }
+        self.previous = std::mem::replace(&mut self.open, open);
+        true
+    }
+
+    // The empty tiles reachable from a tile without diagonal moves
+    fn region(&self, from: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut seen = vec![vec![false; self.width]; self.height];
+        seen[from.1][from.0] = true;
+        let mut region = vec![from];
+        let mut queue = VecDeque::from([from]);
+        while let Some(tile) = queue.pop_front() {
+            for (x, y) in self.neighbours(tile, &NEIGHBOURS[..4]) {
+                if self.open[y][x] && !seen[y][x] {
+                    seen[y][x] = true;
+                    region.push((x, y));
+                    queue.push_back((x, y));
+                }
+            }
+        }
+        region
+    }
+
+    // Joins every cave to the largest one, by repeatedly digging the shortest tunnel from the connected caves to the
+    // closest tile of another cave
+    fn connect(&mut self) {
+        let mut largest: Vec<(usize, usize)> = Vec::new();
+        let mut in_region = vec![vec![false; self.width]; self.height];
+        for (x, y) in self.open_tiles() {
+            if !in_region[y][x] {
+                let region = self.region((x, y));
+                region.iter().for_each(|(x, y)| in_region[*y][*x] = true);
+                if region.len() > largest.len() {
+                    largest = region;
+                }
+            }
+        }
+
+        let mut connected = vec![vec![false; self.width]; self.height];
+        largest.iter().for_each(|(x, y)| connected[*y][*x] = true);
+        let mut remaining = self.open_tiles().len() - largest.len();
+
+        while remaining > 0 {
+            // Search outwards from the connected caves through the walls, remembering where each tile was reached from
+            let mut previous = vec![vec![None; self.width]; self.height];
+            let mut queue: VecDeque<(usize, usize)> = self
+                .open_tiles()
+                .into_iter()
+                .filter(|(x, y)| connected[*y][*x])
+                .collect();
+            let mut reached = None;
+            'search: while let Some(tile) = queue.pop_front() {
+                for (x, y) in self.neighbours(tile, &NEIGHBOURS[..4]) {
+                    if !self.is_inner((x, y)) || connected[y][x] || previous[y][x].is_some() {
+                        continue;
+                    }
+                    previous[y][x] = Some(tile);
+                    if self.open[y][x] {
+                        reached = Some((x, y));
+                        break 'search;
+                    }
+                    queue.push_back((x, y));
+                }
+            }
+
+            // Every inner tile can be dug, so another cave is always reached
+            let Some(reached) = reached else {
+                return;
+            };
+            let mut tile = previous[reached.1][reached.0];
+            while let Some((x, y)) = tile.filter(|(x, y)| !connected[*y][*x]) {
+                self.open[y][x] = true;
+                connected[y][x] = true;
+                tile = previous[y][x];
+            }
+            for (x, y) in self.region(reached) {
+                if !connected[y][x] {
+                    connected[y][x] = true;
+                    remaining = remaining.saturating_sub(1);
+                }
+            }
+        }
+    }
+
+    // Places the start and end tiles in the empty tiles the closest to the top-left and bottom-right corners, and
+    // turns the cave into a tilemap
+    fn into_tilemap(self) -> Result<TileMap, GenerateError> {
+        let open = self.open_tiles();
+        let start = open.iter().min_by_key(|(x, y)| x + y).copied();
+        let end = open
+            .iter()
+            .rev()
+            .filter(|tile| Some(**tile) != start)
+            .max_by_key(|(x, y)| x + y)
+            .copied();
+
+        let mut tiles: Vec<Vec<Tile>> = self
+            .open
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|open| if *open { Tile::Empty } else { Tile::Wall })
+                    .collect()
+            })
+            .collect();
+        for (coord, tile) in [(start, Tile::Start), (end, Tile::End)] {
+            if let Some((x, y)) = coord {
+                tiles[y][x] = tile;
+            }
+        }
+        Ok(TileMap::try_from(tiles)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The tiles that can be walked on, and the ones reachable from the start tile without diagonal moves
+    fn walkable_and_reachable(tilemap: &TileMap) -> (usize, usize) {
+        let tiles: Vec<(usize, usize)> = (0..tilemap.height())
+            .flat_map(|y| (0..tilemap.width()).map(move |x| (x, y)))
+            .filter(|(x, y)| tilemap.get(*x, *y).is_some_and(|tile| tile != Tile::Wall))
+            .collect();
+        let Some(start) = tiles
+            .iter()
+            .find(|(x, y)| tilemap.get(*x, *y) == Some(Tile::Start))
+        else {
+            return (tiles.len(), 0);
+        };
+
+        let mut reached = vec![*start];
+        let mut queue = VecDeque::from([*start]);
+        while let Some((x, y)) = queue.pop_front() {
+            for (dx, dy) in &NEIGHBOURS[..4] {
+                let Some(next) = x.checked_add_signed(*dx).zip(y.checked_add_signed(*dy)) else {
+                    continue;
+                };
+                if tiles.contains(&next) && !reached.contains(&next) {
+                    reached.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+        (tiles.len(), reached.len())
+    }
+
+    #[test]
+    fn every_empty_tile_is_reachable_from_the_start() -> Result<(), GenerateError> {
+        for (width, height) in [(5, 3), (3, 5), (21, 21), (40, 25)] {
+            for fill_ratio in [0., 0.3, 0.45, 0.6] {
+                for smoothing in [0, 4] {
+                    for seed in 0..5 {
+                        let options = CaveOptions {
+                            fill_ratio,
+                            smoothing,
+                        };
+                        let tilemap = match cave(width, height, options, seed) {
+                            Err(GenerateError::EmptyCave { .. }) => continue,
+                            result => result?,
+                        };
+                        let (walkable, reachable) = walkable_and_reachable(&tilemap);
+                        assert_eq!(
+                            walkable, reachable,
+                            "{width}x{height} with {options:?} and seed {seed}"
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn full_fill_ratio_is_rejected() {
+        let options = CaveOptions {
+            fill_ratio: 1.,
+            ..CaveOptions::default()
+        };
+        assert_eq!(
+            cave(21, 21, options, 1),
+            Err(GenerateError::InvalidFillRatio(1.))
+        );
+    }
+
+    #[test]
+    fn smoothing_stops_once_the_cave_settles() -> Result<(), GenerateError> {
+        let options = CaveOptions {
+            smoothing: usize::MAX,
+            ..CaveOptions::default()
+        };
+        cave(41, 41, options, 1)?;
+        Ok(())
+    }
+
+    #[test]
+    fn too_small_caves_are_rejected() {
+        assert_eq!(
+            cave(4, 4, CaveOptions::default(), 1),
+            Err(GenerateError::CaveTooSmall {
+                width: 4,
+                height: 4
+            })
+        );
+    }
+}
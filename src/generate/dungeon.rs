@@ -0,0 +1,422 @@
+use crate::tilemap::{Metadata, Tile, TileMap};
+
+use super::{GenerateError, Rng, SplitMix64};
+
+// The parameters of the generated dungeons
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DungeonOptions {
+    // The smallest width and height of a room, in tiles
+    pub min_room_size: usize,
+    // The largest width and height of a room, in tiles. The space is split until every part fits such a room.
+    pub max_room_size: usize,
+    // Whether some rooms are flooded with mud or water, which can be walked through at a higher cost
+    pub terrain: bool,
+}
+
+impl Default for DungeonOptions {
+    fn default() -> Self {
+        DungeonOptions {
+            min_room_size: 3,
+            max_room_size: 9,
+            terrain: false,
+        }
+    }
+}
+
+impl DungeonOptions {
+    // The metadata saved along with a generated dungeon, so that it can be generated again
+    pub fn metadata(&self, seed: u64) -> Metadata {
+        let mut metadata = Metadata::new();
+        metadata.insert("algorithm", "dungeon");
+        metadata.insert("min-room-size", &self.min_room_size.to_string());
+        metadata.insert("max-room-size", &self.max_room_size.to_string());
+        metadata.insert("terrain", &self.terrain.to_string());
+        metadata.insert("seed", &seed.to_string());
+        metadata
+    }
+
+    // The smallest part of the space that fits a room, with a wall on each side. The sizes saturate rather than
+    // overflow, since a part that large never fits in a tile map anyway.
+    fn min_part_size(&self) -> usize {
+        self.min_room_size.saturating_add(2)
+    }
+
+    // The largest part of the space that is not split any further
+    fn max_part_size(&self) -> usize {
+        self.max_room_size.saturating_add(2)
+    }
+}
+
+// A rectangle of tiles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rect {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl Rect {
+    fn center(&self) -> (usize, usize) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+
+    fn tiles(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (self.y..self.y + self.height)
+            .flat_map(move |y| (self.x..self.x + self.width).map(move |x| (x, y)))
+    }
+}
+
+// A part of the space being partitioned: either split in two other parts, or a leaf holding a room
+struct Part {
+    rect: Rect,
+    children: Option<(usize, usize)>,
+    room: Option<Rect>,
+}
+
+// Generates a dungeon of the given size in tiles (including the outer walls), made of rectangular rooms joined by
+// corridors. The space is split in two at random, over and over (binary space partitioning), until every part fits a
+// room of at most the maximum size, and a room is carved in each part. The rooms of the two halves of every split
+// are then joined by a corridor between their closest rooms, so every room can be reached. The start and end tiles
+// are placed in different rooms, the end room being the farthest from the start room.
+pub fn dungeon(
+    width: usize,
+    height: usize,
+    options: DungeonOptions,
+    seed: u64,
+) -> Result<TileMap, GenerateError> {
+    dungeon_with(width, height, options, &mut SplitMix64::new(seed))
+}
+
+// Generates a dungeon like `dungeon`, drawing the random numbers from the given generator
+pub fn dungeon_with<R: Rng>(
+    width: usize,
+    height: usize,
+    options: DungeonOptions,
+    rng: &mut R,
+) -> Result<TileMap, GenerateError> {
+    let (tiles, _) = layout(width, height, options, rng)?;
+    Ok(TileMap::try_from(tiles)?)
+}
+
+// The tiles of a dungeon, along with the rooms carved in it
+fn layout<R: Rng>(
+    width: usize,
+    height: usize,
+    options: DungeonOptions,
+    rng: &mut R,
+) -> Result<(Vec<Vec<Tile>>, Vec<Rect>), GenerateError> {
+    if options.min_room_size == 0 || options.max_room_size < options.min_room_size {
+        return Err(GenerateError::InvalidRoomSize {
+            min: options.min_room_size,
+            max: options.max_room_size,
+        });
+    }
+    let too_small = GenerateError::TooSmallForRooms {
+        width,
+        height,
+        min_room_size: options.min_room_size,
+    };
+    if width < 2 || height < 2 {
+        return Err(too_small);
+    }
+
+    let parts = partition(
+        Rect {
+            x: 1,
+            y: 1,
+            width: width - 2,
+            height: height - 2,
+        },
+        &options,
+        rng,
+    );
+    let rooms: Vec<Rect> = parts.iter().filter_map(|part| part.room).collect();
+    if rooms.len() < 2 {
+        return Err(too_small);
+    }
+
+    let mut tiles = vec![vec![Tile::Wall; width]; height];
+    for room in &rooms {
+        let floor = match (options.terrain, rng.below(4)) {
+            (true, 0) => Tile::Mud,
+            (true, 1) => Tile::Water,
+            _ => Tile::Empty,
+        };
+        room.tiles().for_each(|(x, y)| tiles[y][x] = floor);
+    }
+
+    // Join the rooms of the two halves of every split, through their closest rooms
+    for part in &parts {
+        let Some((first, second)) = part.children else {
+            continue;
+        };
+        let (first_rooms, second_rooms) = (rooms_in(&parts, first), rooms_in(&parts, second));
+        let closest = first_rooms
+            .iter()
+            .flat_map(|a| second_rooms.iter().map(move |b| (*a, *b)))
+            .min_by_key(|(a, b)| distance(a.center(), b.center()));
+        if let Some((a, b)) = closest {
+            dig_corridor(&mut tiles, a.center(), b.center(), rng.chance(0.5));
+        }
+    }
+
+    // Place the start and end tiles in the floors of different rooms
+    let start_room = rooms[rng.below(rooms.len())];
+    let end_room = rooms
+        .iter()
+        .filter(|room| **room != start_room)
+        .max_by_key(|room| distance(start_room.center(), room.center()))
+        .copied()
+        .unwrap_or(start_room);
+    for (room, tile) in [(start_room, Tile::Start), (end_room, Tile::End)] {
+        let x = room.x + rng.below(room.width);
+        let y = room.y + rng.below(room.height);
+        tiles[y][x] = tile;
+    }
+
+    Ok((tiles, rooms))
+}
+
+// Splits the space until every part fits a room of at most the maximum size (or cannot be split anymore), and places
+// a room in each leaf. The parts are returned with the whole space first, and each part before its children.
+fn partition<R: Rng>(space: Rect, options: &DungeonOptions, rng: &mut R) -> Vec<Part> {
+    let min_part = options.min_part_size();
+    let max_part = options.max_part_size();
+
+    let mut parts = vec![Part {
+        rect: space,
+        children: None,
+        room: None,
+    }];
+    let mut stack = vec![0];
+    while let Some(index) = stack.pop() {
+        let rect = parts[index].rect;
+        let can_split_width = rect.width >= min_part.saturating_mul(2);
+        let can_split_height = rect.height >= min_part.saturating_mul(2);
+        // The whole space is split whenever possible, even if it fits a room, so that there are two rooms
+        let too_wide = rect.width > max_part || index == 0;
+        let too_tall = rect.height > max_part || index == 0;
+
+        // Split across the longest side that is too long, so that the rooms do not come out too narrow
+        let split_width = match (can_split_width && too_wide, can_split_height && too_tall) {
+            (true, true) if rect.width == rect.height => Some(rng.chance(0.5)),
+            (true, true) => Some(rect.width > rect.height),
+            (true, false) => Some(true),
+            (false, true) => Some(false),
+            (false, false) => None,
+        };
+
+        let halves = match split_width {
+            Some(true) => {
+                let split = min_part + rng.below(rect.width - 2 * min_part + 1);
+                Some((
+                    Rect {
+                        width: split,
+                        ..rect
+                    },
+                    Rect {
+                        x: rect.x + split,
+                        width: rect.width - split,
+                        ..rect
+                    },
+                ))
+            }
+            Some(false) => {
+                let split = min_part + rng.below(rect.height - 2 * min_part + 1);
+                Some((
+                    Rect {
+                        height: split,
+                        ..rect
+                    },
+                    Rect {
+                        y: rect.y + split,
+                        height: rect.height - split,
+                        ..rect
+                    },
+                ))
+            }
+            None => None,
+        };
+
+        match halves {
+            Some((first, second)) => {
+                let first_index = parts.len();
+                for rect in [first, second] {
+                    parts.push(Part {
+                        rect,
+                        children: None,
+                        room: None,
+                    });
+                }
+                parts[index].children = Some((first_index, first_index + 1));
+                stack.extend([first_index + 1, first_index]);
+            }
+            None => parts[index].room = place_room(rect, options, rng),
+        }
+    }
+    parts
+}
+
+// A room of random size and position inside a part, with at least one wall between the room and each side of the
+// part, or None if the part is too small for a room
+fn place_room<R: Rng>(part: Rect, options: &DungeonOptions, rng: &mut R) -> Option<Rect> {
+    let max_width = part.width.checked_sub(2)?.min(options.max_room_size);
+    let max_height = part.height.checked_sub(2)?.min(options.max_room_size);
+    if max_width < options.min_room_size || max_height < options.min_room_size {
+        return None;
+    }
+
+    let width = options.min_room_size + rng.below(max_width - options.min_room_size + 1);
+    let height = options.min_room_size + rng.below(max_height - options.min_room_size + 1);
+    Some(Rect {
+        x: part.x + 1 + rng.below(part.width - 2 - width + 1),
+        y: part.y + 1 + rng.below(part.height - 2 - height + 1),
+        width,
+        height,
+    })
+}
+
+// The rooms in a part and all of its descendants
+fn rooms_in(parts: &[Part], index: usize) -> Vec<Rect> {
+    let mut rooms = Vec::new();
+    let mut stack = vec![index];
+    while let Some(index) = stack.pop() {
+        let Some(part) = parts.get(index) else {
+            continue;
+        };
+        rooms.extend(part.room);
+        if let Some((first, second)) = part.children {
+            stack.extend([second, first]);
+        }
+    }
+    rooms
+}
+
+fn distance((ax, ay): (usize, usize), (bx, by): (usize, usize)) -> usize {
+    ax.abs_diff(bx) + ay.abs_diff(by)
+}
+
+// Digs an L-shaped corridor between two tiles, going horizontally first or vertically first. The floors of the rooms
+// it goes through are left as they are. The tiles are always room centres, which lie inside the outer walls, so the
+// corridor never digs through them; tiles outside the map are skipped rather than trusted to that.
+fn dig_corridor(
+    tiles: &mut [Vec<Tile>],
+    (ax, ay): (usize, usize),
+    (bx, by): (usize, usize),
+    horizontal_first: bool,
+) {
+    let corner = if horizontal_first { (bx, ay) } else { (ax, by) };
+    let horizontal = (ax.min(bx)..=ax.max(bx)).map(|x| (x, corner.1));
+    let vertical = (ay.min(by)..=ay.max(by)).map(|y| (corner.0, y));
+    for (x, y) in horizontal.chain(vertical) {
+        if let Some(tile) = tiles
+            .get_mut(y)
+            .and_then(|row| row.get_mut(x))
+            .filter(|tile| **tile == Tile::Wall)
+        {
+            *tile = Tile::Empty;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::maze::Maze;
+    use crate::search::bfs::BreadthFirstSearcher;
+    use crate::test_utils::solve;
+
+    // The index of the room a tile lies in, if any
+    fn room_of(rooms: &[Rect], coord: (usize, usize)) -> Option<usize> {
+        rooms
+            .iter()
+            .position(|room| room.tiles().any(|tile| tile == coord))
+    }
+
+    fn find(tiles: &[Vec<Tile>], wanted: Tile) -> Option<(usize, usize)> {
+        tiles
+            .iter()
+            .enumerate()
+            .find_map(|(y, row)| row.iter().position(|tile| *tile == wanted).map(|x| (x, y)))
+    }
+
+    #[test]
+    fn start_and_end_are_in_different_connected_rooms() -> Result<(), Box<dyn std::error::Error>> {
+        for (width, height) in [(20, 20), (40, 25), (61, 61)] {
+            for seed in 0..10 {
+                let options = DungeonOptions {
+                    terrain: seed % 2 == 0,
+                    ..DungeonOptions::default()
+                };
+                let (tiles, rooms) = layout(width, height, options, &mut SplitMix64::new(seed))?;
+                let start = find(&tiles, Tile::Start).ok_or("no start tile")?;
+                let end = find(&tiles, Tile::End).ok_or("no end tile")?;
+                let start_room = room_of(&rooms, start).ok_or("start outside the rooms")?;
+                let end_room = room_of(&rooms, end).ok_or("end outside the rooms")?;
+                assert_ne!(start_room, end_room, "{width}x{height}, seed {seed}");
+
+                let maze = Rc::new(Maze::try_from(TileMap::try_from(tiles)?)?);
+                assert!(
+                    solve(&mut BreadthFirstSearcher::new(&maze)).is_some(),
+                    "{width}x{height}, seed {seed}"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn terrain_only_floods_room_floors() -> Result<(), Box<dyn std::error::Error>> {
+        let mut flooded = 0;
+        for seed in 0..10 {
+            let options = DungeonOptions {
+                terrain: true,
+                ..DungeonOptions::default()
+            };
+            let (tiles, rooms) = layout(40, 40, options, &mut SplitMix64::new(seed))?;
+            for (y, row) in tiles.iter().enumerate() {
+                for (x, tile) in row.iter().enumerate() {
+                    if matches!(tile, Tile::Mud | Tile::Water) {
+                        assert!(room_of(&rooms, (x, y)).is_some(), "seed {seed}: {x}, {y}");
+                        flooded += 1;
+                    }
+                }
+            }
+
+            let options = DungeonOptions {
+                terrain: false,
+                ..options
+            };
+            let (tiles, _) = layout(40, 40, options, &mut SplitMix64::new(seed))?;
+            assert!(tiles
+                .iter()
+                .flatten()
+                .all(|tile| !matches!(tile, Tile::Mud | Tile::Water)));
+        }
+        assert!(flooded > 0);
+        Ok(())
+    }
+
+    #[test]
+    fn huge_room_sizes_do_not_overflow() -> Result<(), Box<dyn std::error::Error>> {
+        let options = DungeonOptions {
+            max_room_size: usize::MAX,
+            ..DungeonOptions::default()
+        };
+        dungeon(40, 40, options, 1)?;
+
+        let options = DungeonOptions {
+            min_room_size: usize::MAX,
+            max_room_size: usize::MAX,
+            terrain: false,
+        };
+        assert!(matches!(
+            dungeon(40, 40, options, 1),
+            Err(GenerateError::TooSmallForRooms { .. })
+        ));
+        Ok(())
+    }
+}
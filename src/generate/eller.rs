@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+
+use super::{Grid, Rng};
+
+// Eller's algorithm builds the maze row by row, only keeping track of which cells of the current row are already
+// connected (their "set"). In each row, neighbours from different sets are linked at random, then each set is
+// linked down to the next row at least once, so that no part of the maze gets cut off. The last row links all the
+// sets left together.
+pub(super) fn carve<R: Rng>(grid: &mut Grid, rng: &mut R) {
+    let width = grid.width();
+    // The set of each cell of the current row, if it has one yet
+    let mut sets: Vec<Option<usize>> = vec![None; width];
+    let mut next_set = 0;
+
+    for y in 0..grid.height() {
+        let last_row = y + 1 == grid.height();
+        let mut row: Vec<usize> = sets
+            .iter()
+            .map(|set| {
+                set.unwrap_or_else(|| {
+                    next_set += 1;
+                    next_set
+                })
+            })
+            .collect();
+
+        for x in 0..width.saturating_sub(1) {
+            if row[x] != row[x + 1] && (last_row || rng.chance(0.5)) {
+                grid.link((x, y), (x + 1, y));
+                let merged = row[x + 1];
+                let kept = row[x];
+                row.iter_mut()
+                    .filter(|set| **set == merged)
+                    .for_each(|set| *set = kept);
+            }
+        }
+        if last_row {
+            break;
+        }
+
+        // The cells of each set, in a stable order so that the same random numbers make the same maze
+        let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (x, set) in row.iter().enumerate() {
+            members.entry(*set).or_default().push(x);
+        }
+
+        sets = vec![None; width];
+        for (set, mut cells) in members {
+            rng.shuffle(&mut cells);
+            let down = 1 + rng.below(cells.len());
+            for x in cells.into_iter().take(down) {
+                grid.link((x, y), (x, y + 1));
+                sets[x] = Some(set);
+            }
+        }
+    }
+}
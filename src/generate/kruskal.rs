@@ -0,0 +1,46 @@
+use super::{Grid, Rng};
+
+// Randomized Kruskal's algorithm goes through the walls between cells in a random order, and knocks a wall down
+// whenever the cells on both sides are not connected yet. The connected groups of cells are tracked with a
+// union-find structure. The maze grows from many places at once, with a texture close to Prim's algorithm.
+pub(super) fn carve<R: Rng>(grid: &mut Grid, rng: &mut R) {
+    let cells = grid.cells();
+    let mut parents: Vec<usize> = (0..cells.len()).collect();
+
+    // Each wall between two cells, listed once (from the cell above or on the left)
+    let mut walls: Vec<_> = cells
+        .iter()
+        .flat_map(|&(x, y)| {
+            [
+                (x + 1 < grid.width()).then_some(((x, y), (x + 1, y))),
+                (y + 1 < grid.height()).then_some(((x, y), (x, y + 1))),
+            ]
+        })
+        .flatten()
+        .collect();
+    rng.shuffle(&mut walls);
+
+    for (a, b) in walls {
+        let (root_a, root_b) = (
+            find(&mut parents, grid.index(a)),
+            find(&mut parents, grid.index(b)),
+        );
+        if root_a != root_b {
+            parents[root_b] = root_a;
+            grid.link(a, b);
+        }
+    }
+}
+
+// The representative of the group of a cell, flattening the path to it along the way
+fn find(parents: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    let mut current = index;
+    while parents[current] != root {
+        current = std::mem::replace(&mut parents[current], root);
+    }
+    root
+}
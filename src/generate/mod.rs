@@ -0,0 +1,618 @@
+mod aldous_broder;
+mod binary_tree;
+mod braid;
+mod cave;
+mod dungeon;
+mod eller;
+mod kruskal;
+mod prim;
+mod random;
+mod recursive_backtracker;
+mod recursive_division;
+mod sidewinder;
+mod wilson;
+
+use std::{
+    collections::VecDeque,
+    fmt::{Display, Formatter},
+};
+
+use crate::tilemap::{Metadata, Tile, TileMap, TileMapError};
+
+pub use braid::braid;
+pub use cave::{cave, cave_with, CaveOptions};
+pub use dungeon::{dungeon, dungeon_with, DungeonOptions};
+pub use random::{Rng, SplitMix64};
+
+// The position of a cell of the generated maze, in cells rather than in tiles
+type Cell = (usize, usize);
+
+// The algorithms that can generate a maze. All of them generate "perfect" mazes, where there is exactly one path
+// between any two cells, but each one leaves its own texture (long winding corridors, many short dead ends, a bias
+// towards some direction...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    RecursiveBacktracker,
+    Prim,
+    Kruskal,
+    Wilson,
+    AldousBroder,
+    Eller,
+    BinaryTree,
+    Sidewinder,
+    RecursiveDivision,
+}
+
+// Where the start and end tiles are placed in the generated maze
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Placement {
+    // In openings of the outer wall, on different sides when possible, like the hand-made mazes
+    #[default]
+    Border,
+    // In the top-left and bottom-right cells
+    Corners,
+    // In the two cells the farthest apart from each other, so that the solution is as long as possible
+    Farthest,
+}
+
+// Errors that can happen while generating a maze
+#[derive(Debug, Clone, PartialEq)]
+pub enum GenerateError {
+    // The maze is too small to fit two cells inside its outer walls
+    TooSmall {
+        width: usize,
+        height: usize,
+    },
+    // The fraction of dead ends to remove is not between 0 and 1
+    InvalidFraction(f64),
+    // The fill ratio of a cave is not at least 0 and below 1
+    InvalidFillRatio(f64),
+    // The cave is too small to fit the start and end tiles inside its outer walls
+    CaveTooSmall {
+        width: usize,
+        height: usize,
+    },
+    // Every cave generated had fewer than two empty tiles, with the number of caves generated
+    EmptyCave {
+        attempts: usize,
+    },
+    // The smallest size of a room is 0, or larger than the largest size
+    InvalidRoomSize {
+        min: usize,
+        max: usize,
+    },
+    // The dungeon is too small to fit two rooms of the smallest size
+    TooSmallForRooms {
+        width: usize,
+        height: usize,
+        min_room_size: usize,
+    },
+    TileMap(TileMapError),
+}
+
+// The names accepted by Algorithm::from_name, in the order they are listed to the user
+pub const NAMES: [&str; 9] = [
+    "recursive-backtracker",
+    "prim",
+    "kruskal",
+    "wilson",
+    "aldous-broder",
+    "eller",
+    "binary-tree",
+    "sidewinder",
+    "recursive-division",
+];
+
+// The names accepted by Placement::from_name, in the order they are listed to the user
+pub const PLACEMENT_NAMES: [&str; 3] = ["border", "corners", "farthest"];
+
+impl Algorithm {
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::RecursiveBacktracker => "recursive-backtracker",
+            Algorithm::Prim => "prim",
+            Algorithm::Kruskal => "kruskal",
+            Algorithm::Wilson => "wilson",
+            Algorithm::AldousBroder => "aldous-broder",
+            Algorithm::Eller => "eller",
+            Algorithm::BinaryTree => "binary-tree",
+            Algorithm::Sidewinder => "sidewinder",
+            Algorithm::RecursiveDivision => "recursive-division",
+        }
+    }
+
+    // The algorithm with the given name, if any
+    pub fn from_name(name: &str) -> Option<Algorithm> {
+        match name {
+            "recursive-backtracker" => Some(Algorithm::RecursiveBacktracker),
+            "prim" => Some(Algorithm::Prim),
+            "kruskal" => Some(Algorithm::Kruskal),
+            "wilson" => Some(Algorithm::Wilson),
+            "aldous-broder" => Some(Algorithm::AldousBroder),
+            "eller" => Some(Algorithm::Eller),
+            "binary-tree" => Some(Algorithm::BinaryTree),
+            "sidewinder" => Some(Algorithm::Sidewinder),
+            "recursive-division" => Some(Algorithm::RecursiveDivision),
+            _ => None,
+        }
+    }
+}
+
+impl Placement {
+    pub fn name(self) -> &'static str {
+        match self {
+            Placement::Border => "border",
+            Placement::Corners => "corners",
+            Placement::Farthest => "farthest",
+        }
+    }
+
+    // The placement with the given name, if any
+    pub fn from_name(name: &str) -> Option<Placement> {
+        match name {
+            "border" => Some(Placement::Border),
+            "corners" => Some(Placement::Corners),
+            "farthest" => Some(Placement::Farthest),
+            _ => None,
+        }
+    }
+}
+
+impl Display for GenerateError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            GenerateError::TooSmall { width, height } => write!(
+                f,
+                "A maze must be at least 5x3 or 3x5 tiles, to fit two cells inside its outer walls. Found {width}x{height}"
+            ),
+            GenerateError::InvalidFraction(fraction) => write!(
+                f,
+                "The fraction of dead ends to remove must be between 0 and 1. Found {fraction}"
+            ),
+            GenerateError::InvalidFillRatio(ratio) => write!(
+                f,
+                "The fill ratio of a cave must be at least 0 and below 1. Found {ratio}"
+            ),
+            GenerateError::CaveTooSmall { width, height } => write!(
+                f,
+                "A cave must be at least 5x3 or 3x5 tiles, to fit the start and end tiles inside its outer walls. Found {width}x{height}"
+            ),
+            GenerateError::EmptyCave { attempts } => write!(
+                f,
+                "Could not generate a cave with room for the start and end tiles in {attempts} attempts. Try a lower fill ratio"
+            ),
+            GenerateError::InvalidRoomSize { min, max } => write!(
+                f,
+                "The room sizes must be positive, with the smallest size at most the largest size. Found {min} and {max}"
+            ),
+            GenerateError::TooSmallForRooms {
+                width,
+                height,
+                min_room_size,
+            } => write!(
+                f,
+                "A dungeon of {width}x{height} tiles cannot fit two rooms of at least {min_room_size}x{min_room_size} tiles"
+            ),
+            GenerateError::TileMap(error) => write!(f, "Generated an invalid tilemap: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for GenerateError {}
+
+impl From<TileMapError> for GenerateError {
+    fn from(value: TileMapError) -> Self {
+        GenerateError::TileMap(value)
+    }
+}
+
+// The grid the algorithms carve the maze into. Cells sit on the odd coordinates of the tilemap, and the tiles between
+// two cells are the walls that get knocked down to link them. The tiles on even coordinates on both axes are the
+// corners between walls, and always stay walls. With an even width (or height), the last column (or row) is left as
+// a wall.
+struct Grid {
+    width: usize,
+    height: usize,
+    tiles: Vec<Vec<Tile>>,
+}
+
+impl Grid {
+    // A grid of the given size in tiles, full of walls
+    fn new(width: usize, height: usize) -> Result<Grid, GenerateError> {
+        let (cells_width, cells_height) =
+            (width.saturating_sub(1) / 2, height.saturating_sub(1) / 2);
+        if cells_width * cells_height < 2 {
+            return Err(GenerateError::TooSmall { width, height });
+        }
+        Ok(Grid {
+            width: cells_width,
+            height: cells_height,
+            tiles: vec![vec![Tile::Wall; width]; height],
+        })
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    // Every cell, row by row
+    fn cells(&self) -> Vec<Cell> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .collect()
+    }
+
+    // The index of a cell in the list of cells
+    fn index(&self, (x, y): Cell) -> usize {
+        y * self.width + x
+    }
+
+    fn tile(cell: Cell) -> (usize, usize) {
+        (2 * cell.0 + 1, 2 * cell.1 + 1)
+    }
+
+    // The tile between two adjacent cells
+    fn wall_between(a: Cell, b: Cell) -> (usize, usize) {
+        (a.0 + b.0 + 1, a.1 + b.1 + 1)
+    }
+
+    fn set(&mut self, (x, y): (usize, usize), tile: Tile) {
+        if let Some(current) = self.tiles.get_mut(y).and_then(|row| row.get_mut(x)) {
+            *current = tile;
+        }
+    }
+
+    fn is_open(&self, (x, y): (usize, usize)) -> bool {
+        self.tiles
+            .get(y)
+            .and_then(|row| row.get(x))
+            .is_some_and(|tile| *tile != Tile::Wall)
+    }
+
+    // The cells next to a cell, in the order up, left, down, right
+    fn neighbours(&self, (x, y): Cell) -> Vec<Cell> {
+        [
+            (y > 0).then(|| (x, y - 1)),
+            (x > 0).then(|| (x - 1, y)),
+            (y + 1 < self.height).then_some((x, y + 1)),
+            (x + 1 < self.width).then_some((x + 1, y)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    // The cells next to a cell with no wall in between
+    fn linked_neighbours(&self, cell: Cell) -> Vec<Cell> {
+        self.neighbours(cell)
+            .into_iter()
+            .filter(|neighbour| self.is_linked(cell, *neighbour))
+            .collect()
+    }
+
+    fn is_linked(&self, a: Cell, b: Cell) -> bool {
+        self.is_open(Grid::wall_between(a, b))
+    }
+
+    // Knocks down the wall between two adjacent cells, carving both of them
+    fn link(&mut self, a: Cell, b: Cell) {
+        self.set(Grid::tile(a), Tile::Empty);
+        self.set(Grid::tile(b), Tile::Empty);
+        self.set(Grid::wall_between(a, b), Tile::Empty);
+    }
+
+    // Puts the wall between two adjacent cells back
+    fn unlink(&mut self, a: Cell, b: Cell) {
+        self.set(Grid::wall_between(a, b), Tile::Wall);
+    }
+
+    // Links every cell to all of its neighbours, leaving only the corners between walls
+    fn link_all(&mut self) {
+        for cell in self.cells() {
+            for neighbour in self.neighbours(cell) {
+                self.link(cell, neighbour);
+            }
+        }
+    }
+
+    // The cell the farthest from the given one through the maze (the last one found on ties)
+    fn farthest_from(&self, from: Cell) -> Cell {
+        let mut visited = vec![false; self.width * self.height];
+        visited[self.index(from)] = true;
+        let mut queue = VecDeque::from([from]);
+        let mut last = from;
+        while let Some(cell) = queue.pop_front() {
+            last = cell;
+            for neighbour in self.linked_neighbours(cell) {
+                let index = self.index(neighbour);
+                if !visited[index] {
+                    visited[index] = true;
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        last
+    }
+
+    // The openings of the outer wall: the border tiles right next to a cell, with the side of the maze they are on
+    fn openings(&self) -> Vec<((usize, usize), usize)> {
+        let (tiles_width, tiles_height) = (self.tiles[0].len(), self.tiles.len());
+        let top = (0..self.width).map(|x| ((2 * x + 1, 0), 0));
+        let left = (0..self.height).map(|y| ((0, 2 * y + 1), 1));
+        // The bottom and right walls only have openings if no extra row or column of walls was left
+        let bottom = (0..self.width)
+            .filter(|_| tiles_height == 2 * self.height + 1)
+            .map(move |x| ((2 * x + 1, tiles_height - 1), 2));
+        let right = (0..self.height)
+            .filter(|_| tiles_width == 2 * self.width + 1)
+            .map(move |y| ((tiles_width - 1, 2 * y + 1), 3));
+        top.chain(left).chain(bottom).chain(right).collect()
+    }
+
+    // Places the start and end tiles, and turns the grid into a tilemap
+    fn into_tilemap<R: Rng>(
+        mut self,
+        placement: Placement,
+        rng: &mut R,
+    ) -> Result<TileMap, GenerateError> {
+        let (start, end) = match placement {
+            Placement::Border => {
+                let openings = self.openings();
+                let start = openings[rng.below(openings.len())];
+                // The top and left walls always have openings, so there is always another side for the end tile
+                let other_sides: Vec<_> = openings
+                    .into_iter()
+                    .filter(|(_, side)| *side != start.1)
+                    .collect();
+                let end = rng.choose(&other_sides).copied().unwrap_or(start);
+                (start.0, end.0)
+            }
+            Placement::Corners => (
+                Grid::tile((0, 0)),
+                Grid::tile((self.width - 1, self.height - 1)),
+            ),
+            Placement::Farthest => {
+                let cells = self.cells();
+                let cell = cells[rng.below(cells.len())];
+                let start = self.farthest_from(cell);
+                let end = self.farthest_from(start);
+                (Grid::tile(start), Grid::tile(end))
+            }
+        };
+        self.set(start, Tile::Start);
+        self.set(end, Tile::End);
+        Ok(TileMap::try_from(self.tiles)?)
+    }
+}
+
+// Generates a maze of the given size in tiles (including the outer walls) with the given algorithm. The same seed
+// and parameters always generate the same maze, on any platform.
+pub fn generate(
+    algorithm: Algorithm,
+    width: usize,
+    height: usize,
+    placement: Placement,
+    seed: u64,
+) -> Result<TileMap, GenerateError> {
+    generate_with(
+        algorithm,
+        width,
+        height,
+        placement,
+        &mut SplitMix64::new(seed),
+    )
+}
+
+// Generates a maze like `generate`, drawing the random numbers from the given generator
+pub fn generate_with<R: Rng>(
+    algorithm: Algorithm,
+    width: usize,
+    height: usize,
+    placement: Placement,
+    rng: &mut R,
+) -> Result<TileMap, GenerateError> {
+    let mut grid = Grid::new(width, height)?;
+
+    match algorithm {
+        Algorithm::RecursiveBacktracker => recursive_backtracker::carve(&mut grid, rng),
+        Algorithm::Prim => prim::carve(&mut grid, rng),
+        Algorithm::Kruskal => kruskal::carve(&mut grid, rng),
+        Algorithm::Wilson => wilson::carve(&mut grid, rng),
+        Algorithm::AldousBroder => aldous_broder::carve(&mut grid, rng),
+        Algorithm::Eller => eller::carve(&mut grid, rng),
+        Algorithm::BinaryTree => binary_tree::carve(&mut grid, rng),
+        Algorithm::Sidewinder => sidewinder::carve(&mut grid, rng),
+        Algorithm::RecursiveDivision => recursive_division::carve(&mut grid, rng),
+    }
+
+    grid.into_tilemap(placement, rng)
+}
+
+// The metadata saved along with a generated maze, so that it can be generated again
+pub fn metadata(algorithm: Algorithm, placement: Placement, seed: u64) -> Metadata {
+    let mut metadata = Metadata::new();
+    metadata.insert("algorithm", algorithm.name());
+    metadata.insert("placement", placement.name());
+    metadata.insert("seed", &seed.to_string());
+    metadata
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, error::Error, rc::Rc};
+
+    use super::{generate, metadata, Algorithm, Placement, Rng, SplitMix64, NAMES};
+    use crate::maze::Maze;
+    use crate::search::bfs::BreadthFirstSearcher;
+    use crate::test_utils::solve;
+    use crate::tilemap::{Metadata, Tile, TileMap};
+
+    fn algorithms() -> impl Iterator<Item = Algorithm> {
+        NAMES.into_iter().filter_map(Algorithm::from_name)
+    }
+
+    fn is_open(tilemap: &TileMap, (x, y): (usize, usize)) -> bool {
+        tilemap.get(x, y).is_some_and(|tile| tile != Tile::Wall)
+    }
+
+    // Checks that the cells of a generated maze (the tiles at odd coordinates) are all open and form a spanning tree:
+    // there is exactly one link fewer than cells, every cell can be reached from the first one, and no tile is open
+    // other than the cells, the links between them and the start and end tiles
+    fn assert_perfect(tilemap: &TileMap, context: &str) {
+        let (width, height) = ((tilemap.width() - 1) / 2, (tilemap.height() - 1) / 2);
+        let tile = |(x, y): (usize, usize)| (2 * x + 1, 2 * y + 1);
+        let cells: Vec<_> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .collect();
+        assert!(
+            cells.iter().all(|cell| is_open(tilemap, tile(*cell))),
+            "{context}: closed cell"
+        );
+
+        let linked = |(x, y): (usize, usize)| {
+            [
+                (y > 0).then(|| (x, y - 1)),
+                (x > 0).then(|| (x - 1, y)),
+                (y + 1 < height).then_some((x, y + 1)),
+                (x + 1 < width).then_some((x + 1, y)),
+            ]
+            .into_iter()
+            .flatten()
+            .filter(move |(nx, ny)| is_open(tilemap, (x + nx + 1, y + ny + 1)))
+        };
+        let links: usize = cells
+            .iter()
+            .map(|cell| linked(*cell).count())
+            .sum::<usize>()
+            / 2;
+        assert_eq!(links, cells.len() - 1, "{context}: not a tree");
+
+        let mut visited = vec![vec![false; width]; height];
+        visited[0][0] = true;
+        let mut queue = VecDeque::from([(0, 0)]);
+        let mut reached = 0;
+        while let Some(cell) = queue.pop_front() {
+            reached += 1;
+            for (x, y) in linked(cell) {
+                if !visited[y][x] {
+                    visited[y][x] = true;
+                    queue.push_back((x, y));
+                }
+            }
+        }
+        assert_eq!(reached, cells.len(), "{context}: not connected");
+
+        // Tiles with both coordinates even are the corners between cells, and must stay walls. Open tiles on the
+        // border can only be the start and end openings.
+        for y in 0..tilemap.height() {
+            for x in 0..tilemap.width() {
+                let border = x == 0 || y == 0 || x >= 2 * width || y >= 2 * height;
+                if (border || (x % 2 == 0 && y % 2 == 0)) && is_open(tilemap, (x, y)) {
+                    assert!(
+                        matches!(tilemap.get(x, y), Some(Tile::Start | Tile::End)),
+                        "{context}: open tile at {x}, {y}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_algorithm_generates_perfect_solvable_mazes() -> Result<(), Box<dyn Error>> {
+        for algorithm in algorithms() {
+            for placement in [Placement::Border, Placement::Corners, Placement::Farthest] {
+                for (width, height) in [(3, 5), (5, 3), (21, 15), (20, 14), (4, 6)] {
+                    for seed in 0..5 {
+                        let context =
+                            format!("{algorithm:?} {placement:?} {width}x{height} {seed}");
+                        let tilemap = generate(algorithm, width, height, placement, seed)?;
+                        assert_eq!(
+                            (tilemap.width(), tilemap.height()),
+                            (width, height),
+                            "{context}"
+                        );
+                        assert_perfect(&tilemap, &context);
+
+                        let maze = Rc::new(Maze::try_from(tilemap)?);
+                        let path = solve(&mut BreadthFirstSearcher::new(&maze))
+                            .ok_or(format!("{context}: the end cannot be reached"))?;
+                        assert!(path.len() > 1, "{context}");
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn same_seed_generates_the_same_maze() -> Result<(), Box<dyn Error>> {
+        for algorithm in algorithms() {
+            for placement in [Placement::Border, Placement::Corners, Placement::Farthest] {
+                let first = generate(algorithm, 31, 21, placement, 1234)?;
+                let second = generate(algorithm, 31, 21, placement, 1234)?;
+                assert_eq!(first.to_string(), second.to_string(), "{algorithm:?}");
+                assert_eq!(first.to_npy(), second.to_npy(), "{algorithm:?}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn random_numbers_match_the_reference_splitmix64() {
+        let mut rng = SplitMix64::new(0);
+        assert_eq!(rng.next_u64(), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(rng.next_u64(), 0x6e78_9e6a_a1b9_65f4);
+    }
+
+    #[test]
+    fn known_seed_generates_the_known_maze() -> Result<(), Box<dyn Error>> {
+        let maze = generate(
+            Algorithm::RecursiveBacktracker,
+            11,
+            7,
+            Placement::Border,
+            42,
+        )?;
+        let expected = "\
+11111111111
+10000010001
+10101010101
+10101010103
+10101011101
+10101000001
+11111111121
+";
+        assert_eq!(maze.to_string(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn metadata_round_trip() -> Result<(), Box<dyn Error>> {
+        let expected = metadata(Algorithm::Wilson, Placement::Farthest, 99);
+        assert_eq!(expected.get("algorithm"), Some("wilson"));
+        assert_eq!(expected.get("placement"), Some("farthest"));
+        assert_eq!(expected.get("seed"), Some("99"));
+
+        let maze = generate(Algorithm::Wilson, 21, 21, Placement::Farthest, 99)?;
+        let text = maze.to_text_with_metadata(&expected);
+        assert_eq!(Metadata::from_text(&text), expected);
+        assert_eq!(TileMap::try_from(text)?, maze);
+
+        let npy = maze.to_npy_with_metadata(&expected);
+        assert_eq!(Metadata::from_npy(&npy)?, expected);
+        assert_eq!(TileMap::from_npy(&npy)?, maze);
+        Ok(())
+    }
+
+    #[test]
+    fn files_without_metadata_have_empty_metadata() -> Result<(), Box<dyn Error>> {
+        let maze = generate(Algorithm::Prim, 11, 11, Placement::Border, 5)?;
+        assert!(Metadata::from_text(&maze.to_string()).is_empty());
+        assert!(Metadata::from_npy(&maze.to_npy())?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use super::{Grid, Rng};
+
+// Randomized Prim's algorithm grows the maze from a single cell: at each step, it picks a random cell on the
+// frontier (the cells next to the maze) and links it to a random neighbour already in the maze. The maze grows
+// evenly in all directions, which makes many short dead ends.
+pub(super) fn carve<R: Rng>(grid: &mut Grid, rng: &mut R) {
+    let cells = grid.cells();
+    let mut in_maze = vec![false; cells.len()];
+    let mut in_frontier = vec![false; cells.len()];
+    let mut frontier = Vec::new();
+
+    let first = cells[rng.below(cells.len())];
+    in_maze[grid.index(first)] = true;
+    for neighbour in grid.neighbours(first) {
+        in_frontier[grid.index(neighbour)] = true;
+        frontier.push(neighbour);
+    }
+
+    while !frontier.is_empty() {
+        let cell = frontier.swap_remove(rng.below(frontier.len()));
+        let (maze_neighbours, outside): (Vec<_>, Vec<_>) = grid
+            .neighbours(cell)
+            .into_iter()
+            .partition(|neighbour| in_maze[grid.index(*neighbour)]);
+        if let Some(neighbour) = rng.choose(&maze_neighbours).copied() {
+            grid.link(cell, neighbour);
+        }
+        in_maze[grid.index(cell)] = true;
+
+        for neighbour in outside {
+            let index = grid.index(neighbour);
+            if !in_frontier[index] {
+                in_frontier[index] = true;
+                frontier.push(neighbour);
+            }
+        }
+    }
+}
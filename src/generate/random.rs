@@ -0,0 +1,47 @@
+// The source of randomness of the generators. Only `next_u64` has to be implemented, and every other method is built
+// on it with integer arithmetic only, so that a given sequence of numbers always shapes the same maze, whatever the
+// platform.
+pub trait Rng {
+    fn next_u64(&mut self) -> u64;
+
+    // A number in 0..bound, or 0 if the bound is 0
+    fn below(&mut self, bound: usize) -> usize {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+
+    // True with the given probability. The 53 bits of the number are converted exactly, so this is deterministic too.
+    fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+
+    fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        items.get(self.below(items.len()))
+    }
+
+    // Shuffles the items in place (Fisher-Yates)
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            items.swap(index, self.below(index + 1));
+        }
+    }
+}
+
+// A small pseudo-random number generator (SplitMix64), so that generating mazes needs no external crate.
+// It is fast and good enough to shape mazes, but must not be used for anything security related.
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> SplitMix64 {
+        SplitMix64(seed)
+    }
+}
+
+impl Rng for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
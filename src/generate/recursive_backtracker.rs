@@ -0,0 +1,27 @@
+use super::{Grid, Rng};
+
+// The recursive backtracker walks randomly from cell to cell, carving as it goes and never entering a cell twice.
+// When it gets stuck, it backtracks to the last cell with a neighbour left to visit. This makes long, winding
+// corridors with few dead ends. The walk is kept on an explicit stack, so large mazes do not overflow the call stack.
+pub(super) fn carve<R: Rng>(grid: &mut Grid, rng: &mut R) {
+    let cells = grid.cells();
+    let mut visited = vec![false; cells.len()];
+    let first = cells[rng.below(cells.len())];
+    visited[grid.index(first)] = true;
+
+    let mut stack = vec![first];
+    while let Some(cell) = stack.last().copied() {
+        let unvisited: Vec<_> = grid
+            .neighbours(cell)
+            .into_iter()
+            .filter(|neighbour| !visited[grid.index(*neighbour)])
+            .collect();
+        let Some(next) = rng.choose(&unvisited).copied() else {
+            stack.pop();
+            continue;
+        };
+        visited[grid.index(next)] = true;
+        grid.link(cell, next);
+        stack.push(next);
+    }
+}
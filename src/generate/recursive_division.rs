@@ -0,0 +1,43 @@
+use super::{Grid, Rng};
+
+// Recursive division starts from an open grid and adds walls instead of carving passages: it splits the area in two
+// with a wall along its shorter side, leaves a single gap in that wall, and does the same in both halves until every
+// area is a single cell wide. This makes long straight walls and a boxy texture. The areas left to split are kept on
+// an explicit stack.
+pub(super) fn carve<R: Rng>(grid: &mut Grid, rng: &mut R) {
+    grid.link_all();
+
+    // The areas left to split, as (x, y, width, height) in cells
+    let mut areas = vec![(0, 0, grid.width(), grid.height())];
+    while let Some((x, y, width, height)) = areas.pop() {
+        if width < 2 && height < 2 {
+            continue;
+        }
+        let horizontal = match (width < 2, height < 2) {
+            (true, _) => true,
+            (_, true) => false,
+            _ if width != height => height > width,
+            _ => rng.chance(0.5),
+        };
+
+        if horizontal {
+            // A wall below row y + split, with a gap in one column
+            let split = rng.below(height - 1);
+            let gap = x + rng.below(width);
+            for column in (x..x + width).filter(|column| *column != gap) {
+                grid.unlink((column, y + split), (column, y + split + 1));
+            }
+            areas.push((x, y, width, split + 1));
+            areas.push((x, y + split + 1, width, height - split - 1));
+        } else {
+            // A wall right of column x + split, with a gap in one row
+            let split = rng.below(width - 1);
+            let gap = y + rng.below(height);
+            for row in (y..y + height).filter(|row| *row != gap) {
+                grid.unlink((x + split, row), (x + split + 1, row));
+            }
+            areas.push((x, y, split + 1, height));
+            areas.push((x + split + 1, y, width - split - 1, height));
+        }
+    }
+}
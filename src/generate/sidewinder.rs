@@ -0,0 +1,23 @@
+use super::{Grid, Rng};
+
+// The sidewinder algorithm goes through the grid row by row, carving runs of cells linked horizontally. Each run is
+// closed at random (or at the right edge), and then linked to the row above through one of its cells, picked at
+// random. The top row is a single straight corridor, and every path goes up without ever coming back down.
+pub(super) fn carve<R: Rng>(grid: &mut Grid, rng: &mut R) {
+    for y in 0..grid.height() {
+        let mut run_start = 0;
+        for x in 0..grid.width() {
+            let at_right_edge = x + 1 == grid.width();
+            let close_run = at_right_edge || (y > 0 && rng.chance(0.5));
+            if !close_run {
+                grid.link((x, y), (x + 1, y));
+                continue;
+            }
+            if y > 0 {
+                let up = run_start + rng.below(x - run_start + 1);
+                grid.link((up, y), (up, y - 1));
+            }
+            run_start = x + 1;
+        }
+    }
+}
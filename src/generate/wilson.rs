@@ -0,0 +1,41 @@
+use super::{Grid, Rng};
+
+// Wilson's algorithm adds cells to the maze through loop-erased random walks: from a cell outside the maze, it walks
+// randomly until it reaches the maze, remembering only the last direction it left each cell through (which erases the
+// loops of the walk), then carves the walk into the maze. Like Aldous-Broder, it picks uniformly among all possible
+// mazes, but it is much faster once the maze has grown.
+pub(super) fn carve<R: Rng>(grid: &mut Grid, rng: &mut R) {
+    let mut cells = grid.cells();
+    let mut in_maze = vec![false; cells.len()];
+    let first = cells[rng.below(cells.len())];
+    in_maze[grid.index(first)] = true;
+
+    // The cell each cell of the current walk was left towards
+    let mut exits = vec![None; cells.len()];
+
+    rng.shuffle(&mut cells);
+    for origin in cells {
+        if in_maze[grid.index(origin)] {
+            continue;
+        }
+
+        let mut current = origin;
+        while !in_maze[grid.index(current)] {
+            let Some(next) = rng.choose(&grid.neighbours(current)).copied() else {
+                break;
+            };
+            exits[grid.index(current)] = Some(next);
+            current = next;
+        }
+
+        let mut current = origin;
+        while !in_maze[grid.index(current)] {
+            in_maze[grid.index(current)] = true;
+            let Some(next) = exits[grid.index(current)] else {
+                break;
+            };
+            grid.link(current, next);
+            current = next;
+        }
+    }
+}
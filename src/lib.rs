@@ -1,3 +1,8 @@
+pub mod generate;
 pub mod maze;
+pub mod npy;
 pub mod search;
 pub mod tilemap;
+
+#[cfg(test)]
+mod test_utils;
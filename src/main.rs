@@ -1,9 +1,19 @@
-use maze_runner_rs::maze::{Coordinates, Maze, MazeNode};
-use maze_runner_rs::search::Searcher;
-use maze_runner_rs::search::{a_star, bfs, dfs};
+use maze_runner_rs::generate;
+use maze_runner_rs::maze::{
+    Connectivity, Coordinates, CornerCutting, Maze, MazeEditor, MazeNode, STRAIGHT_STEP_COST,
+};
+use maze_runner_rs::search::{
+    a_star, ara_star, beam, bfs, bidirectional_bfs, d_star_lite, dead_end_filling, dfs, dijkstra,
+    greedy, heuristic_check, heuristics, ida_star, iddfs, jps, landmarks::Landmarks, tremaux,
+    wall_follower, weighted_a_star,
+};
+use maze_runner_rs::search::{SearchMode, Searcher};
+#[cfg(debug_assertions)]
+use maze_runner_rs::tilemap::Metadata;
 use maze_runner_rs::tilemap::{EmptyTileState, TileMap};
 use std::collections::HashMap;
 use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::{env, fs};
 
 use macroquad::prelude::*;
@@ -11,6 +21,18 @@ use macroquad::prelude::*;
 const STEP_DELAY: f64 = 0.;
 const DRAW_DELAY: f64 = 1. / 24.;
 
+// The epsilon of weighted A* and the initial epsilon of ARA*, unless another one is given
+const DEFAULT_EPSILON: f64 = 2.5;
+
+// The number of paths kept at each depth by beam search, unless another one is given
+const DEFAULT_BEAM_WIDTH: usize = 10;
+
+// The size in tiles of the generated mazes, unless another one is given
+const DEFAULT_GENERATED_SIZE: (usize, usize) = (21, 21);
+
+// The maximum number of violations of each kind printed by the heuristic check
+const HEURISTIC_CHECK_LINES: usize = 10;
+
 // Static mutable variable to store the number of steps taken
 static mut STEPS: u64 = 0;
 
@@ -27,20 +49,283 @@ async fn main() {
     // Get the algorithm from the command line arguments, defaulting to A*
     let algorithm_str = args.get(2).cloned().unwrap_or(String::from("a-star"));
 
-    // Read the file into a string
-    let Ok(tilemap_str) = fs::read_to_string(filepath) else {
-        eprintln!("File not found: {filepath}");
+    // Get the search mode from the optional "--tree" flag, defaulting to graph search
+    let search_mode = if args.iter().skip(3).any(|arg| arg == "--tree") {
+        SearchMode::Tree
+    } else {
+        SearchMode::Graph
+    };
+
+    // Get the connectivity from the optional "--diagonal" and "--cut-corners" flags, defaulting to 4 directions
+    let connectivity = if args.iter().skip(3).any(|arg| arg == "--cut-corners") {
+        Connectivity::Eight(CornerCutting::Always)
+    } else if args.iter().skip(3).any(|arg| arg == "--diagonal") {
+        Connectivity::Eight(CornerCutting::Never)
+    } else {
+        Connectivity::Four
+    };
+
+    // Get the heuristic from the optional "--heuristic <name>" flag, defaulting to the exact distance on an empty maze
+    let heuristic_str = flag_value(&args, "--heuristic").unwrap_or(match connectivity {
+        Connectivity::Four => "manhattan",
+        Connectivity::Eight(_) => "octile",
+    });
+    // The "alt" heuristic is not a named heuristic, as its landmarks can only be selected once the maze is loaded
+    let named_heuristic = heuristics::from_name(heuristic_str);
+    if named_heuristic.is_none() && heuristic_str != "alt" {
+        eprintln!(
+            "Invalid heuristic: Heuristic must be [{} | \"alt\"]. \"{}\" is not a valid heuristic",
+            heuristics::NAMES
+                .map(|name| format!("\"{name}\""))
+                .join(" | "),
+            heuristic_str
+        );
         return;
+    }
+
+    // Get the number of landmarks of the ALT heuristic from the optional "--landmarks <count>" flag, defaulting to 8
+    let Ok(landmark_count) = flag_value(&args, "--landmarks").map_or(Ok(8), str::parse::<usize>)
+    else {
+        eprintln!("Invalid landmark count: Landmark count must be a non-negative integer");
+        return;
+    };
+
+    // Whether the landmarks of the ALT heuristic are cached next to the maze file, from the optional "--cache-landmarks" flag
+    let cache_landmarks = args.iter().skip(3).any(|arg| arg == "--cache-landmarks");
+
+    // Get the weight of the heuristic from the optional "--weight <weight>" flag, defaulting to 1
+    let weight = match flag_value(&args, "--weight").map(str::parse::<f64>) {
+        None => 1.,
+        Some(Ok(weight)) if weight >= 0. && weight.is_finite() => weight,
+        Some(_) => {
+            eprintln!("Invalid weight: Weight must be a non-negative finite number");
+            return;
+        }
     };
 
-    // Parse the string into a TileMap object
-    let Ok(tilemap): Result<TileMap, _> = tilemap_str.try_into() else {
-        eprintln!("File is not a proper tilemap");
+    // Get the epsilon of weighted A* and ARA* from the optional "--epsilon <epsilon>" flag
+    let epsilon = match flag_value(&args, "--epsilon").map(str::parse::<f64>) {
+        None => DEFAULT_EPSILON,
+        Some(Ok(epsilon)) if epsilon >= 1. && epsilon.is_finite() => epsilon,
+        Some(_) => {
+            eprintln!(
+                "Invalid epsilon: Epsilon must be a finite number greater than or equal to 1"
+            );
+            return;
+        }
+    };
+
+    // Weighted A* and ARA* weigh the heuristic by epsilon, and report their suboptimality bound from it, so they take
+    // "--epsilon" instead of "--weight", and the other searchers only take "--weight"
+    let weighs_by_epsilon = matches!(algorithm_str.as_str(), "weighted-a-star" | "ara-star");
+    if weighs_by_epsilon && flag_value(&args, "--weight").is_some() {
+        eprintln!("Invalid weight: {algorithm_str} weighs the heuristic with --epsilon instead of --weight");
+        return;
+    }
+    if !weighs_by_epsilon && flag_value(&args, "--epsilon").is_some() {
+        eprintln!("Invalid epsilon: Only weighted-a-star and ara-star take --epsilon, use --weight instead");
+        return;
+    }
+
+    // Get the width of beam search from the optional "--beam-width <width>" flag
+    let Some(beam_width) = flag_value(&args, "--beam-width")
+        .map_or(Ok(DEFAULT_BEAM_WIDTH), str::parse::<usize>)
+        .ok()
+        .filter(|width| *width > 0)
+    else {
+        eprintln!("Invalid beam width: Beam width must be a positive integer");
+        return;
+    };
+
+    // If the "--generate <algorithm>" flag is set, generate a new maze and write it to the file before solving it
+    if let Some(generator_str) = flag_value(&args, "--generate") {
+        // The maze algorithms generate perfect mazes, and the other generators generate open maps
+        let generator = match generator_str {
+            "cave" | "dungeon" => None,
+            name => match generate::Algorithm::from_name(name) {
+                Some(algorithm) => Some(algorithm),
+                None => {
+                    eprintln!(
+                        "Invalid generator: Generator must be [{} | \"cave\" | \"dungeon\"]. \"{}\" is not a valid generator",
+                        generate::NAMES.map(|name| format!("\"{name}\"")).join(" | "),
+                        generator_str
+                    );
+                    return;
+                }
+            },
+        };
+
+        // Get the size of the maze from the optional "--size <width>x<height>" flag
+        let size = flag_value(&args, "--size").map_or(Some(DEFAULT_GENERATED_SIZE), |size| {
+            let (width, height) = size.split_once('x')?;
+            Some((width.parse().ok()?, height.parse().ok()?))
+        });
+        let Some((width, height)) = size else {
+            eprintln!("Invalid size: Size must be given as <width>x<height>, such as 21x21");
+            return;
+        };
+
+        // Get the placement of the start and end tiles from the optional "--placement <placement>" flag
+        let placement_str = flag_value(&args, "--placement").unwrap_or("border");
+        let Some(placement) = generate::Placement::from_name(placement_str) else {
+            eprintln!(
+                "Invalid placement: Placement must be [{}]. \"{}\" is not a valid placement",
+                generate::PLACEMENT_NAMES
+                    .map(|name| format!("\"{name}\""))
+                    .join(" | "),
+                placement_str
+            );
+            return;
+        };
+        // Caves and dungeons place the start and end tiles on their own, so they do not take a placement
+        if generator.is_none() && flag_value(&args, "--placement").is_some() {
+            eprintln!(
+                "Invalid placement: The \"{generator_str}\" generator does not take a placement"
+            );
+            return;
+        }
+
+        // Get the seed from the optional "--seed <seed>" flag, or from the current time. The same seed always
+        // generates the same maze, so it is saved with the maze, and printed when it comes from the time.
+        let seed = match flag_value(&args, "--seed").map(str::parse::<u64>) {
+            Some(Ok(seed)) => seed,
+            None => {
+                let seed = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |duration| duration.as_nanos() as u64);
+                println!("Generating with seed {seed}");
+                seed
+            }
+            Some(Err(_)) => {
+                eprintln!("Invalid seed: Seed must be a non-negative integer");
+                return;
+            }
+        };
+
+        // Get the fraction of dead ends to remove from the optional "--braid <fraction>" flag
+        let Ok(braid) = flag_value(&args, "--braid").map_or(Ok(0.), str::parse::<f64>) else {
+            eprintln!("Invalid braid: Braid must be a number between 0 and 1");
+            return;
+        };
+
+        // Get the parameters of caves from the optional "--fill-ratio <ratio>" and "--smoothing <steps>" flags
+        let default_cave = generate::CaveOptions::default();
+        let Ok(fill_ratio) = flag_value(&args, "--fill-ratio")
+            .map_or(Ok(default_cave.fill_ratio), str::parse::<f64>)
+        else {
+            eprintln!("Invalid fill ratio: Fill ratio must be a number at least 0 and below 1");
+            return;
+        };
+        let Ok(smoothing) = flag_value(&args, "--smoothing")
+            .map_or(Ok(default_cave.smoothing), str::parse::<usize>)
+        else {
+            eprintln!("Invalid smoothing: Smoothing must be a non-negative integer");
+            return;
+        };
+        let cave_options = generate::CaveOptions {
+            fill_ratio,
+            smoothing,
+        };
+
+        // Get the parameters of dungeons from the optional "--min-room <size>", "--max-room <size>" and "--terrain" flags
+        let default_dungeon = generate::DungeonOptions::default();
+        let room_sizes = (
+            flag_value(&args, "--min-room")
+                .map_or(Ok(default_dungeon.min_room_size), str::parse::<usize>),
+            flag_value(&args, "--max-room")
+                .map_or(Ok(default_dungeon.max_room_size), str::parse::<usize>),
+        );
+        let (Ok(min_room_size), Ok(max_room_size)) = room_sizes else {
+            eprintln!("Invalid room size: Room sizes must be positive integers");
+            return;
+        };
+        let dungeon_options = generate::DungeonOptions {
+            min_room_size,
+            max_room_size,
+            terrain: args.iter().skip(3).any(|arg| arg == "--terrain"),
+        };
+
+        // The maze is braided with the same random number generator, so that the seed is enough to generate it again
+        let mut rng = generate::SplitMix64::new(seed);
+        let generated = match (generator, generator_str) {
+            (Some(algorithm), _) => {
+                generate::generate_with(algorithm, width, height, placement, &mut rng)
+                    .map(|tilemap| (tilemap, generate::metadata(algorithm, placement, seed)))
+            }
+            (None, "dungeon") => generate::dungeon_with(width, height, dungeon_options, &mut rng)
+                .map(|tilemap| (tilemap, dungeon_options.metadata(seed))),
+            (None, _) => generate::cave_with(width, height, cave_options, &mut rng)
+                .map(|tilemap| (tilemap, cave_options.metadata(seed))),
+        };
+        let generated = generated.and_then(|(tilemap, metadata)| {
+            if braid == 0. {
+                Ok((tilemap, metadata))
+            } else {
+                generate::braid(&tilemap, braid, &mut rng).map(|tilemap| (tilemap, metadata))
+            }
+        });
+        let (tilemap, mut metadata) = match generated {
+            Ok(generated) => generated,
+            Err(err) => {
+                eprintln!("Could not generate the maze: {err}");
+                return;
+            }
+        };
+        if braid != 0. {
+            metadata.insert("braid", &braid.to_string());
+        }
+        let contents = if filepath.ends_with(".npy") {
+            tilemap.to_npy_with_metadata(&metadata)
+        } else {
+            tilemap.to_text_with_metadata(&metadata).into_bytes()
+        };
+        if let Err(err) = fs::write(filepath, contents) {
+            eprintln!("Could not write the maze to {filepath}: {err}");
+            return;
+        }
+    }
+
+    // Read the file into a byte buffer
+    let Ok(file_bytes) = fs::read(filepath) else {
+        eprintln!("File not found: {filepath}");
         return;
     };
 
+    // Parse the file into a TileMap object, as a NumPy array or as text depending on the extension
+    let tilemap_result: Result<TileMap, _> = if filepath.ends_with(".npy") {
+        TileMap::from_npy(&file_bytes)
+    } else {
+        String::from_utf8_lossy(&file_bytes).into_owned().try_into()
+    };
+    let tilemap = match tilemap_result {
+        Ok(tilemap) => tilemap,
+        Err(err) => {
+            eprintln!("File is not a proper tilemap: {err}");
+            return;
+        }
+    };
+
+    // Print the metadata saved with the maze, such as the seed it was generated with
+    #[cfg(debug_assertions)]
+    {
+        let metadata = if filepath.ends_with(".npy") {
+            Metadata::from_npy(&file_bytes).unwrap_or_default()
+        } else {
+            Metadata::from_text(&String::from_utf8_lossy(&file_bytes))
+        };
+        metadata
+            .iter()
+            .for_each(|(key, value)| println!("{key}: {value}"));
+    }
+
     // Create a maze from the tilemap
-    let maze: Rc<Maze> = Rc::new(tilemap.into());
+    let maze: Rc<Maze> = match Maze::try_from(tilemap) {
+        Ok(maze) => Rc::new(maze.with_connectivity(connectivity)),
+        Err(err) => {
+            eprintln!("File is not a proper maze: {err}");
+            return;
+        }
+    };
 
     let mut done = false; // Whether the search is done
     let mut delta_time: f64 = 0f64; // Time since the last iteration of the loop
@@ -52,21 +337,102 @@ async fn main() {
     // Hashmap to store the state of empty tiles, for rendering only (Considering, Visited, Focused)
     let mut empty_tile_states: HashMap<Coordinates, EmptyTileState> = HashMap::new();
 
+    // Define the heuristic function for the informed searchers, selecting the landmarks first for the ALT heuristic
+    let base_heuristic: heuristics::BoxedHeuristic = match named_heuristic {
+        Some(heuristic) => Box::new(heuristic),
+        None => {
+            let cache_path = cache_landmarks.then(|| landmarks_cache_path(filepath, connectivity));
+            let landmarks = load_landmarks(&maze, landmark_count, cache_path);
+            Box::new(move |node: &MazeNode, end_node: &MazeNode| {
+                landmarks.estimate(node.get_coordinates(), end_node.get_coordinates())
+            })
+        }
+    };
+    let heuristic = heuristics::weighted(base_heuristic, weight);
+
+    // If the "--check-heuristic" flag is set, report where the heuristic breaks admissibility or consistency
+    if args.iter().skip(3).any(|arg| arg == "--check-heuristic") {
+        let report = heuristic_check::check(&maze, &heuristic);
+        println!("Heuristic check: {report}");
+        report
+            .overestimates
+            .iter()
+            .take(HEURISTIC_CHECK_LINES)
+            .for_each(|overestimate| println!("  {overestimate}"));
+        report
+            .inconsistencies
+            .iter()
+            .take(HEURISTIC_CHECK_LINES)
+            .for_each(|inconsistency| println!("  {inconsistency}"));
+    }
+
+    // The editor lets the walls be toggled while searching, for the searchers that follow the changes
+    let mut editor = MazeEditor::new(maze.clone());
+
     // Define the searcher algorithm, based on the command line argument
     let mut searcher: Box<dyn Searcher> = match algorithm_str.as_str() {
         "dfs" => Box::new(dfs::DepthFirstSearcher::new(&maze)),
-        "bfs" => Box::new(bfs::BreadthFirstSearcher::new(&maze)),
-        "a-star" => {
-            // Define the heuristic function for A* (Manhattan distance)
-            let heuristic = Box::new(|node: &MazeNode, end_node: &MazeNode| {
-                (Maze::manhattan_distance(node.get_coordinates(), end_node.get_coordinates()))
-                    as u64
-            });
-            Box::new(a_star::AStarSearcher::new(maze.clone(), heuristic))
-        }
+        "iddfs" => Box::new(iddfs::IterativeDeepeningSearcher::new(maze.clone())),
+        "bfs" => Box::new(bfs::BreadthFirstSearcher::with_mode(&maze, search_mode)),
+        "bidirectional-bfs" => Box::new(bidirectional_bfs::BidirectionalBfsSearcher::new(
+            maze.clone(),
+        )),
+        "dijkstra" => Box::new(dijkstra::DijkstraSearcher::with_mode(
+            maze.clone(),
+            search_mode,
+        )),
+        "greedy" => Box::new(greedy::GreedyBestFirstSearcher::with_mode(
+            maze.clone(),
+            Box::new(heuristic),
+            search_mode,
+        )),
+        "a-star" => Box::new(a_star::AStarSearcher::with_mode(
+            maze.clone(),
+            Box::new(heuristic),
+            search_mode,
+        )),
+        "beam" => Box::new(beam::BeamSearcher::new(
+            maze.clone(),
+            Box::new(heuristic),
+            beam_width,
+        )),
+        "weighted-a-star" => Box::new(weighted_a_star::WeightedAStarSearcher::with_mode(
+            maze.clone(),
+            Box::new(heuristic),
+            epsilon,
+            search_mode,
+        )),
+        "ara-star" => Box::new(ara_star::AraStarSearcher::new(
+            maze.clone(),
+            Box::new(heuristic),
+            epsilon,
+        )),
+        "d-star-lite" => Box::new(d_star_lite::DStarLiteSearcher::watching(
+            &mut editor,
+            Box::new(heuristic),
+        )),
+        "ida-star" => Box::new(ida_star::IdaStarSearcher::new(
+            maze.clone(),
+            Box::new(heuristic),
+        )),
+        "jps" => Box::new(jps::JumpPointSearcher::new(
+            maze.clone(),
+            Box::new(heuristic),
+        )),
+        "left-wall" => Box::new(wall_follower::WallFollowerSearcher::new(
+            maze.clone(),
+            wall_follower::Hand::Left,
+        )),
+        "right-wall" => Box::new(wall_follower::WallFollowerSearcher::new(
+            maze.clone(),
+            wall_follower::Hand::Right,
+        )),
+        "pledge" => Box::new(wall_follower::PledgeSearcher::new(maze.clone())),
+        "tremaux" => Box::new(tremaux::TremauxSearcher::new(maze.clone())),
+        "dead-end-filling" => Box::new(dead_end_filling::DeadEndFiller::new(maze.clone())),
         _ => {
             eprintln!(
-                "Invalid algorithm: Algorithm must be [\"dfs\" | \"bfs\" | \"a-star\"]. \"{}\" is not a valid algorithm",
+                "Invalid algorithm: Algorithm must be [\"dfs\" | \"iddfs\" | \"bfs\" | \"bidirectional-bfs\" | \"dijkstra\" | \"greedy\" | \"a-star\" | \"beam\" | \"weighted-a-star\" | \"ara-star\" | \"d-star-lite\" | \"ida-star\" | \"jps\" | \"left-wall\" | \"right-wall\" | \"pledge\" | \"tremaux\" | \"dead-end-filling\"]. \"{}\" is not a valid algorithm",
                 algorithm_str
             );
             return;
@@ -81,6 +447,19 @@ async fn main() {
         step_timer += delta_time;
         draw_timer += delta_time;
 
+        // Walls can be toggled by clicking on the maze, for the searchers that repair their solution (D* Lite)
+        if algorithm_str == "d-star-lite" && is_mouse_button_pressed(MouseButton::Left) {
+            if let Some(coord) = tile_at(&editor.maze(), mouse_position()) {
+                match editor.toggle_wall(coord) {
+                    Ok(_) => {
+                        empty_tile_states.remove(&coord);
+                        done = false;
+                    }
+                    Err(err) => eprintln!("Cannot toggle the wall: {err}"),
+                }
+            }
+        }
+
         // Only step if the delay has passed and the search is not done
         if step_timer >= STEP_DELAY && !done {
             step_timer -= STEP_DELAY;
@@ -91,7 +470,7 @@ async fn main() {
         // Note: this has no "done" condition, as otherwise the window would crash as soon as a solution was found
         if draw_timer >= DRAW_DELAY {
             draw_timer -= DRAW_DELAY * (draw_timer / DRAW_DELAY).floor();
-            draw(&maze, &mut empty_tile_states);
+            draw(&editor.maze(), &mut empty_tile_states);
             next_frame().await;
         }
 
@@ -100,32 +479,114 @@ async fn main() {
 }
 
 
+// The value following the given flag in the command line arguments, if any
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .skip(3)
+        .skip_while(|arg| *arg != flag)
+        .nth(1)
+        .map(String::as_str)
+}
+
+// The cache file of the landmarks, next to the maze file. The distances depend on the connectivity, so each one has its own file.
+fn landmarks_cache_path(filepath: &str, connectivity: Connectivity) -> String {
+    let suffix = match connectivity {
+        Connectivity::Four => "4",
+        Connectivity::Eight(CornerCutting::Never) => "8",
+        Connectivity::Eight(CornerCutting::Always) => "8-cut",
+    };
+    format!("{filepath}.landmarks-{suffix}.npy")
+}
+
+// Selects the landmarks of the ALT heuristic, reading them from the cache file instead if it matches the maze,
+// and writing them to it otherwise
+fn load_landmarks(maze: &Rc<Maze>, count: usize, cache_path: Option<String>) -> Landmarks {
+    let Some(cache_path) = cache_path else {
+        return Landmarks::select(maze, count);
+    };
+
+    let cached = fs::read(&cache_path)
+        .ok()
+        .and_then(|bytes| Landmarks::from_npy(maze, &bytes))
+        .filter(|landmarks| landmarks.landmarks().len() == count.min(Landmarks::max_count(maze)));
+    if let Some(landmarks) = cached {
+        return landmarks;
+    }
+
+    let landmarks = Landmarks::select(maze, count);
+    if let Err(err) = fs::write(&cache_path, landmarks.to_npy()) {
+        eprintln!("Could not cache the landmarks to {cache_path}: {err}");
+    }
+    landmarks
+}
+
+// Reports that the search stopped without a path, with the reason given by incomplete searchers
+fn report_no_path(searcher: &dyn Searcher) {
+    eprintln!("No path found");
+    if let Some(failure) = searcher.get_failure() {
+        eprintln!("{failure}");
+    }
+}
+
 // Advances the search by one step
 fn step(
     searcher: &mut Box<dyn Searcher>,
     empty_tile_states: &mut HashMap<Coordinates, EmptyTileState>,
 ) -> bool {
     // Get the next node to expand, otherwise raise an error message and stop the search
-    let Some(path) = searcher.get_current_path().cloned() else {
-        eprintln!("No path found");
+    let Some(path) = searcher.get_current_path() else {
+        report_no_path(searcher.as_ref());
         return true;
     };
 
+    let iteration = searcher.get_iteration();
+    let solution_cost = searcher.get_solution().and_then(|solution| solution.cost());
+
     let Some(node) = searcher.next() else {
         eprintln!("No node left to expand");
         return true;
     };
 
+    // If the searcher started a new pass, clear the rendering state so that each pass is shown on its own
+    let new_iteration = searcher.get_iteration();
+    if new_iteration.is_some() && new_iteration != iteration {
+        #[cfg(debug_assertions)]
+        println!("Starting pass {}", new_iteration.unwrap_or_default());
+        empty_tile_states.clear();
+    }
+
+    // If an anytime searcher published a better solution, report it and keep it highlighted during the next passes
+    if let Some(solution) = searcher
+        .get_solution()
+        .filter(|solution| solution.cost() != solution_cost)
+    {
+        println!(
+            "Improved path found.\nCost of path found: {:.2}\nSuboptimality bound: {:.2}",
+            solution.cost().unwrap_or_default() as f64 / STRAIGHT_STEP_COST as f64,
+            searcher.get_suboptimality_bound().unwrap_or(1.)
+        );
+        empty_tile_states.retain(|_, state| *state != EmptyTileState::Solution);
+        empty_tile_states.extend(
+            solution
+                .iter()
+                .map(MazeNode::get_coordinates)
+                .zip(std::iter::repeat(EmptyTileState::Solution)),
+        );
+    }
+
     // If the selected node is the final node, the search is done
     if node.get_tile() == maze_runner_rs::tilemap::Tile::End {
         #[cfg(debug_assertions)]
         println!("Path found!");
-        unsafe {
-            println!(
-                "Search done.\nNodes considered: {}\nLength of path found: {}",
-                STEPS,
-                path.iter().len()
-            );
+        let steps = unsafe { STEPS };
+        println!(
+            "Search done.\nNodes considered: {}\nLength of path found: {}\nCost of path found: {:.2}",
+            steps,
+            path.len(),
+            path.cost().unwrap_or_default() as f64 / STRAIGHT_STEP_COST as f64
+        );
+        if let Some(bound) = searcher.get_suboptimality_bound() {
+            println!("Suboptimality bound: {bound:.2}");
         }
         return true;
     }
@@ -134,34 +595,42 @@ fn step(
 
     // The following scope is only relevant for rendering purposes, it does not affect the search itself
     {
-        // Set all "Focused" nodes to "Visited" (Only relevant for rendering)
+        // Set all "Focused" nodes to "Visited", or to "Filled" for the solvers that fill the maze in (Only relevant for rendering)
+        let developed_state = if searcher.fills_developed_nodes() {
+            EmptyTileState::Filled
+        } else {
+            EmptyTileState::Visited
+        };
         empty_tile_states
             .iter_mut()
             .filter(|(_, state)| **state == EmptyTileState::Focused)
-            .for_each(|(_, state)| *state = EmptyTileState::Visited);
+            .for_each(|(_, state)| *state = developed_state);
 
         // Set all the nodes being considered to "Considering" (Only relevant for rendering)
+        // Tiles of the published solution keep their state, so that the solution stays visible
         searcher.get_considered_nodes().iter().for_each(|node| {
-            empty_tile_states.insert(node.get_coordinates(), EmptyTileState::Considering);
+            if empty_tile_states.get(&node.get_coordinates()) != Some(&EmptyTileState::Solution) {
+                empty_tile_states.insert(node.get_coordinates(), EmptyTileState::Considering);
+            }
         });
 
         // Set the current path to "Focused" (Only relevant for rendering)
         if let Some(current_path) = searcher.get_current_path() {
-            empty_tile_states.extend(
-                current_path
-                    .iter()
-                    .map(MazeNode::get_coordinates)
-                    .zip(std::iter::repeat(EmptyTileState::Focused)),
-            );
+            for coord in current_path.iter().map(MazeNode::get_coordinates) {
+                if empty_tile_states.get(&coord) != Some(&EmptyTileState::Solution) {
+                    empty_tile_states.insert(coord, EmptyTileState::Focused);
+                }
+            }
             false
         } else {
-            eprintln!("No path found");
+            report_no_path(searcher.as_ref());
             true
         }
     }
 }
 
-fn draw(maze: &Rc<Maze>, empty_tile_states: &mut HashMap<Coordinates, EmptyTileState>) {
+// The size of the tiles and the x and y offsets of the maze, so that it fills the screen while staying centered
+fn layout(maze: &Rc<Maze>) -> (f32, f32, f32) {
     // Define the size of the tiles, based on the screen size and the maze size
     let tile_size = f32::min(
         screen_width() / maze.width() as f32,
@@ -172,6 +641,23 @@ fn draw(maze: &Rc<Maze>, empty_tile_states: &mut HashMap<Coordinates, EmptyTileS
     let x_offset = (screen_width() - (tile_size * maze.width() as f32)) / 2f32;
     let y_offset = (screen_height() - (tile_size * maze.height() as f32)) / 2f32;
 
+    (tile_size, x_offset, y_offset)
+}
+
+// The coordinates of the tile under a position on the screen, if any
+fn tile_at(maze: &Rc<Maze>, (x_pos, y_pos): (f32, f32)) -> Option<Coordinates> {
+    let (tile_size, x_offset, y_offset) = layout(maze);
+    let (x, y) = (
+        (x_pos - x_offset) / tile_size,
+        (y_pos - y_offset) / tile_size,
+    );
+    (x >= 0. && y >= 0. && (x as usize) < maze.width() && (y as usize) < maze.height())
+        .then_some((x as usize, y as usize))
+}
+
+fn draw(maze: &Rc<Maze>, empty_tile_states: &mut HashMap<Coordinates, EmptyTileState>) {
+    let (tile_size, x_offset, y_offset) = layout(maze);
+
     // Struct to store a streak of tiles with the same color, for faster rendering
     struct TileStreak {
         start_idx: usize,
@@ -190,7 +676,7 @@ fn draw(maze: &Rc<Maze>, empty_tile_states: &mut HashMap<Coordinates, EmptyTileS
         // The x-component of the position of the row
         let x_pos = x_offset + (x_idx as f32) * tile_size;
         for y_idx in 0..maze.height() {
-            // The tile to render (Empty, Wall, Start or End)
+            // The tile to render (Empty, Wall, Start, End, Mud or Water)
             #[allow(clippy::expect_used)]
             let tile = maze
                 .get_node((x_idx, y_idx))
@@ -202,12 +688,19 @@ fn draw(maze: &Rc<Maze>, empty_tile_states: &mut HashMap<Coordinates, EmptyTileS
                 maze_runner_rs::tilemap::Tile::Start => Some(YELLOW),
                 maze_runner_rs::tilemap::Tile::End => Some(GREEN),
                 maze_runner_rs::tilemap::Tile::Wall => Some(WHITE),
-                maze_runner_rs::tilemap::Tile::Empty => {
-                    match empty_tile_states.get(&(x_idx, y_idx)) {
-                        None => None,
-                        Some(EmptyTileState::Visited) => Some(SKYBLUE),
-                        Some(EmptyTileState::Focused) => Some(ORANGE),
-                        Some(EmptyTileState::Considering) => Some(RED),
+                // Walkable tiles are colored by their search state, falling back to the terrain color
+                maze_runner_rs::tilemap::Tile::Empty
+                | maze_runner_rs::tilemap::Tile::Mud
+                | maze_runner_rs::tilemap::Tile::Water => {
+                    match (empty_tile_states.get(&(x_idx, y_idx)), tile) {
+                        (Some(EmptyTileState::Visited), _) => Some(SKYBLUE),
+                        (Some(EmptyTileState::Focused), _) => Some(ORANGE),
+                        (Some(EmptyTileState::Considering), _) => Some(RED),
+                        (Some(EmptyTileState::Solution), _) => Some(GOLD),
+                        (Some(EmptyTileState::Filled), _) => Some(GRAY),
+                        (None, maze_runner_rs::tilemap::Tile::Mud) => Some(BROWN),
+                        (None, maze_runner_rs::tilemap::Tile::Water) => Some(DARKBLUE),
+                        (None, _) => None,
                     }
                 }
             };
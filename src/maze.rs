@@ -1,34 +1,100 @@
 use std::{
-    fmt::{Debug, Formatter},
+    collections::HashMap,
+    fmt::{Debug, Display, Formatter},
     rc::Rc,
+    sync::mpsc::{channel, Receiver, Sender},
 };
 
-use crate::tilemap::{Tile, TileMap, TileMapIter};
+use crate::tilemap::{Tile, TileMap, TileMapError, TileMapIter};
 
 pub type Coordinates = (usize, usize);
 
-#[derive(PartialEq)]
+// Type-alias "cost" for better semantics
+pub type Cost = u64;
+
+// Costs are fixed-point values, so that diagonal steps can cost (approximately) √2 times a straight step.
+// The diagonal cost is rounded down, so that distance estimates built on these constants never overestimate.
+pub const STRAIGHT_STEP_COST: Cost = 100;
+pub const DIAGONAL_STEP_COST: Cost = 141;
+
+// Whether diagonal moves are allowed, and if so, when they may cut past the corner of a wall
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Connectivity {
+    // Moves only go up, left, down or right
+    #[default]
+    Four,
+    // Moves may also go diagonally
+    Eight(CornerCutting),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CornerCutting {
+    // A diagonal move is only allowed if both cells it passes by are free
+    #[default]
+    Never,
+    // A diagonal move is allowed whenever its destination is free, even squeezing between two walls
+    Always,
+}
+
+// Maze is Eq so that comparing two MazeNodes short-circuits on the Rc pointer instead of comparing whole maps
+#[derive(PartialEq, Eq, Clone)]
 pub struct Maze {
     map: TileMap,
     start_coord: Coordinates,
     end_coord: Coordinates,
+    connectivity: Connectivity,
 }
 
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Eq, Clone)]
 pub struct MazeNode {
     coord: (usize, usize),
     maze: Rc<Maze>,
 }
 
+// The diagonal neighbours are only ever set on mazes with 8-directional connectivity
 pub struct Neighbours {
     pub up: Option<MazeNode>,
     pub left: Option<MazeNode>,
     pub down: Option<MazeNode>,
     pub right: Option<MazeNode>,
+    pub up_left: Option<MazeNode>,
+    pub down_left: Option<MazeNode>,
+    pub down_right: Option<MazeNode>,
+    pub up_right: Option<MazeNode>,
 }
 
 pub struct NeighboursIter(Neighbours);
 
+// Errors that can happen while editing a maze
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MazeEditError {
+    // The coordinates are outside of the maze
+    OutOfBounds(Coordinates),
+    // The start and end tiles cannot be changed, as a maze has exactly one of each
+    StartOrEnd(Coordinates),
+    // Start and End tiles cannot be placed by editing
+    InvalidTile(Tile),
+}
+
+// A change made to a maze: the cell, its tile before and after the change, and the maze after the change
+#[derive(Clone, PartialEq, Eq)]
+pub struct MazeChange {
+    pub coord: Coordinates,
+    pub previous: Tile,
+    pub current: Tile,
+    pub maze: Rc<Maze>,
+}
+
+// The MazeEditor owns a maze that can change over time, and notifies every subscriber of each change.
+// Nodes keep pointing to the maze they were created from, so editing a maze that is still in use creates a new copy
+// (copy-on-write), and searchers see the changes only once they are notified.
+pub struct MazeEditor {
+    maze: Rc<Maze>,
+    subscribers: Vec<Sender<MazeChange>>,
+    // The tiles covered by the walls placed with toggle_wall, which are restored when the walls are toggled off
+    covered: HashMap<Coordinates, Tile>,
+}
+
 impl IntoIterator for Neighbours {
     type Item = MazeNode;
 
@@ -39,7 +105,8 @@ impl IntoIterator for Neighbours {
     }
 }
 
-// The NeighboursIter struct is an iterator over the Neighbours struct, in the order up, left, down, right.
+// The NeighboursIter struct is an iterator over the Neighbours struct, in the order up, left, down, right,
+// followed by the diagonals in the order up-left, down-left, down-right, up-right.
 impl Iterator for NeighboursIter {
     type Item = MazeNode;
 
@@ -49,6 +116,10 @@ impl Iterator for NeighboursIter {
             .or_else(|| self.0.left.take())
             .or_else(|| self.0.down.take())
             .or_else(|| self.0.right.take())
+            .or_else(|| self.0.up_left.take())
+            .or_else(|| self.0.down_left.take())
+            .or_else(|| self.0.down_right.take())
+            .or_else(|| self.0.up_right.take())
     }
 }
 
@@ -74,17 +145,85 @@ impl Maze {
         }
     }
 
+    pub fn tilemap(&self) -> &TileMap {
+        &self.map
+    }
+
+    pub fn connectivity(&self) -> Connectivity {
+        self.connectivity
+    }
+
+    pub fn with_connectivity(self, connectivity: Connectivity) -> Maze {
+        Maze {
+            connectivity,
+            ..self
+        }
+    }
+
     pub fn width(&self) -> usize {
         self.map.width()
     }
 
+    // Replaces the tile at the given coordinates with any tile other than Start or End.
+    // Start and End tiles themselves cannot be replaced.
+    pub fn set_tile(&mut self, coord: Coordinates, tile: Tile) -> Result<Tile, MazeEditError> {
+        if tile == Tile::Start || tile == Tile::End {
+            return Err(MazeEditError::InvalidTile(tile));
+        }
+        match self.map.get(coord.0, coord.1) {
+            None => Err(MazeEditError::OutOfBounds(coord)),
+            Some(Tile::Start | Tile::End) => Err(MazeEditError::StartOrEnd(coord)),
+            Some(_) => self
+                .map
+                .set(coord.0, coord.1, tile)
+                .ok_or(MazeEditError::OutOfBounds(coord)),
+        }
+    }
+
     pub fn height(&self) -> usize {
         self.map.height()
     }
 
+    fn is_walkable(&self, coord: Coordinates) -> bool {
+        self.map
+            .get(coord.0, coord.1)
+            .is_some_and(|tile| tile != Tile::Wall)
+    }
+
+    // The cost of moving from a cell to an adjacent one, which is the cost of a straight or diagonal step
+    // multiplied by the weight of the destination tile.
+    // Returns None if the move is not allowed by the connectivity of the maze, or if the destination cannot be walked through.
+    pub fn step_cost(&self, from: Coordinates, to: Coordinates) -> Option<Cost> {
+        self.map.get(from.0, from.1)?;
+        let weight = self.map.get(to.0, to.1).and_then(Tile::weight)?;
+
+        match (
+            usize::abs_diff(from.0, to.0),
+            usize::abs_diff(from.1, to.1),
+            self.connectivity,
+        ) {
+            (1, 0, _) | (0, 1, _) => Some(weight * STRAIGHT_STEP_COST),
+            (1, 1, Connectivity::Eight(CornerCutting::Always)) => Some(weight * DIAGONAL_STEP_COST),
+            (1, 1, Connectivity::Eight(CornerCutting::Never))
+                if self.is_walkable((from.0, to.1)) && self.is_walkable((to.0, from.1)) =>
+            {
+                Some(weight * DIAGONAL_STEP_COST)
+            }
+            _ => None,
+        }
+    }
+
     pub fn manhattan_distance(coord1: (usize, usize), coord2: (usize, usize)) -> usize {
         usize::abs_diff(coord1.0, coord2.0) + usize::abs_diff(coord1.1, coord2.1)
     }
+
+    // The cost of the cheapest path between two cells on an empty 8-connected grid: diagonal steps while
+    // both coordinates differ, then straight steps.
+    pub fn octile_distance(coord1: (usize, usize), coord2: (usize, usize)) -> Cost {
+        let dx = usize::abs_diff(coord1.0, coord2.0) as Cost;
+        let dy = usize::abs_diff(coord1.1, coord2.1) as Cost;
+        DIAGONAL_STEP_COST * dx.min(dy) + STRAIGHT_STEP_COST * dx.abs_diff(dy)
+    }
 }
 
 impl MazeNode {
@@ -100,74 +239,185 @@ impl MazeNode {
         (self.coord.0, self.coord.1)
     }
 
-    pub fn get_neighbors(&self) -> Neighbours {
-        let up_coord = match self.coord.1 {
-            0 => None,
-            _ => Some((self.coord.0, self.coord.1 - 1)),
-        };
-        let left_coord = match self.coord.0 {
-            0 => None,
-            _ => Some((self.coord.0 - 1, self.coord.1)),
-        };
-        let down_coord = match self.coord.1 {
-            y if y == self.maze.height() - 1 => None,
-            _ => Some((self.coord.0, self.coord.1 + 1)),
-        };
-        let right_coord = match self.coord.0 {
-            x if x == self.maze.width() - 1 => None,
-            _ => Some((self.coord.0 + 1, self.coord.1)),
-        };
+    pub fn step_cost(&self, to: &MazeNode) -> Option<Cost> {
+        self.maze.step_cost(self.coord, to.coord)
+    }
 
-        let up = up_coord
-            .and_then(|coord| self.maze.get_node(coord))
-            .filter(|node| node.get_tile() != Tile::Wall);
-        let left = left_coord
-            .and_then(|coord| self.maze.get_node(coord))
-            .filter(|node| node.get_tile() != Tile::Wall);
-        let down = down_coord
-            .and_then(|coord| self.maze.get_node(coord))
-            .filter(|node| node.get_tile() != Tile::Wall);
-        let right = right_coord
-            .and_then(|coord| self.maze.get_node(coord))
-            .filter(|node| node.get_tile() != Tile::Wall);
+    // The neighbour at the given offset, if the maze allows moving there from this node
+    fn neighbour(&self, offset: (isize, isize)) -> Option<MazeNode> {
+        let coord = (
+            self.coord.0.checked_add_signed(offset.0)?,
+            self.coord.1.checked_add_signed(offset.1)?,
+        );
+        self.maze.step_cost(self.coord, coord)?;
+        self.maze.get_node(coord)
+    }
 
+    pub fn get_neighbors(&self) -> Neighbours {
         Neighbours {
-            up,
-            left,
-            down,
-            right,
+            up: self.neighbour((0, -1)),
+            left: self.neighbour((-1, 0)),
+            down: self.neighbour((0, 1)),
+            right: self.neighbour((1, 0)),
+            up_left: self.neighbour((-1, -1)),
+            down_left: self.neighbour((-1, 1)),
+            down_right: self.neighbour((1, 1)),
+            up_right: self.neighbour((1, -1)),
         }
     }
 }
 
-impl From<TileMap> for Maze {
-    fn from(value: TileMap) -> Self {
+impl TryFrom<TileMap> for Maze {
+    type Error = TileMapError;
+
+    fn try_from(value: TileMap) -> Result<Self, Self::Error> {
         let iterator: TileMapIter = (&value).into();
-        #[allow(clippy::expect_used)]
         let start_coord = iterator
             .filter(|(tile, _, _)| *tile == Tile::Start)
             .map(|(_, x, y)| (x, y))
             .next()
-            .expect("TileMap has no start node");
+            .ok_or(TileMapError::StartCount(0))?;
 
         let iterator: TileMapIter = (&value).into();
-        #[allow(clippy::expect_used)]
         let end_coord = iterator
             .filter(|(tile, _, _)| *tile == Tile::End)
             .map(|(_, x, y)| (x, y))
             .next()
-            .expect("TileMap has no end node");
+            .ok_or(TileMapError::EndCount(0))?;
 
-        Maze {
+        Ok(Maze {
             map: value,
             start_coord,
             end_coord,
+            connectivity: Connectivity::default(),
+        })
+    }
+}
+
+impl MazeEditor {
+    pub fn new(maze: Rc<Maze>) -> MazeEditor {
+        MazeEditor {
+            maze,
+            subscribers: Vec::new(),
+            covered: HashMap::new(),
+        }
+    }
+
+    // The maze as of the last change
+    pub fn maze(&self) -> Rc<Maze> {
+        self.maze.clone()
+    }
+
+    // Returns a receiver that gets every change made from now on
+    pub fn subscribe(&mut self) -> Receiver<MazeChange> {
+        let (sender, receiver) = channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    pub fn set_tile(
+        &mut self,
+        coord: Coordinates,
+        tile: Tile,
+    ) -> Result<MazeChange, MazeEditError> {
+        let change = self.replace_tile(coord, tile)?;
+        self.covered.remove(&coord);
+        Ok(change)
+    }
+
+    // Turns a walkable tile into a wall, or a wall back into the tile it covered (an empty tile for the walls that
+    // were not placed by toggling), so that toggling twice leaves mud and water as they were.
+    pub fn toggle_wall(&mut self, coord: Coordinates) -> Result<MazeChange, MazeEditError> {
+        match self.maze.map.get(coord.0, coord.1) {
+            None => Err(MazeEditError::OutOfBounds(coord)),
+            Some(Tile::Wall) => {
+                let tile = self.covered.get(&coord).copied().unwrap_or(Tile::Empty);
+                self.set_tile(coord, tile)
+            }
+            Some(tile) => {
+                let change = self.replace_tile(coord, Tile::Wall)?;
+                self.covered.insert(coord, tile);
+                Ok(change)
+            }
+        }
+    }
+
+    // Replaces a tile and notifies the subscribers of the change
+    fn replace_tile(
+        &mut self,
+        coord: Coordinates,
+        tile: Tile,
+    ) -> Result<MazeChange, MazeEditError> {
+        let previous = Rc::make_mut(&mut self.maze).set_tile(coord, tile)?;
+        let change = MazeChange {
+            coord,
+            previous,
+            current: tile,
+            maze: self.maze.clone(),
+        };
+        // Subscribers whose receiver has been dropped are forgotten
+        self.subscribers
+            .retain(|subscriber| subscriber.send(change.clone()).is_ok());
+        Ok(change)
+    }
+}
+
+impl Display for MazeEditError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            MazeEditError::OutOfBounds(coord) => write!(f, "{coord:?} is outside of the maze"),
+            MazeEditError::StartOrEnd(coord) => {
+                write!(
+                    f,
+                    "The start and end tiles cannot be changed, found one at {coord:?}"
+                )
+            }
+            MazeEditError::InvalidTile(tile) => write!(f, "{tile:?} tiles cannot be placed"),
         }
     }
 }
 
+impl std::error::Error for MazeEditError {}
+
 impl Debug for MazeNode {
     fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
         write!(f, "{:?}", self.coord)
     }
 }
+
+impl Display for Maze {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        write!(f, "{}", self.map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, rc::Rc};
+
+    use super::{Maze, MazeEditor};
+    use crate::tilemap::{Tile, TileMap};
+
+    #[test]
+    fn toggling_twice_restores_the_tile() -> Result<(), Box<dyn Error>> {
+        let tilemap = TileMap::try_from(String::from("11111\n12451\n10131\n11111"))?;
+        let mut editor = MazeEditor::new(Rc::new(Maze::try_from(tilemap)?));
+        for (coord, tile) in [
+            ((2, 1), Tile::Mud),
+            ((3, 1), Tile::Water),
+            ((1, 2), Tile::Empty),
+        ] {
+            assert_eq!(editor.toggle_wall(coord)?.current, Tile::Wall);
+            assert_eq!(editor.toggle_wall(coord)?.current, tile);
+        }
+
+        // Walls that were not placed by toggling become empty tiles
+        assert_eq!(editor.toggle_wall((2, 2))?.current, Tile::Empty);
+
+        // Setting a tile forgets the tile covered by the wall
+        editor.toggle_wall((2, 1))?;
+        editor.set_tile((2, 1), Tile::Wall)?;
+        assert_eq!(editor.toggle_wall((2, 1))?.current, Tile::Empty);
+        Ok(())
+    }
+}
@@ -0,0 +1,320 @@
+// Minimal reader and writer for the NumPy .npy format, restricted to 2-dimensional integer arrays.
+// Format reference: https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html
+
+use std::fmt::{Display, Formatter};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NpyError {
+    // The file does not start with the NPY magic string
+    NotNpy,
+    UnsupportedVersion(u8),
+    TruncatedHeader,
+    // The header dict could not be parsed, with the name of the offending key
+    MalformedHeader(&'static str),
+    MissingKey(&'static str),
+    // Only integer element types are supported, the unsupported 'descr' is kept as-is
+    UnsupportedDtype(String),
+    // Only 2-dimensional arrays are supported, the unsupported shape is kept as-is
+    UnsupportedShape(Vec<usize>),
+    // The payload does not hold exactly the number of elements required by the shape
+    PayloadLength { expected: usize, found: usize },
+    // An unsigned 64-bit value is too large to be read as an i64
+    ValueOutOfRange(u64),
+}
+
+impl Display for NpyError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            NpyError::NotNpy => write!(f, "File is not in the NPY format"),
+            NpyError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported NPY version {version}")
+            }
+            NpyError::TruncatedHeader => write!(f, "NPY header is truncated"),
+            NpyError::MalformedHeader(key) => {
+                write!(f, "Malformed value for '{key}' in NPY header")
+            }
+            NpyError::MissingKey(key) => write!(f, "NPY header is missing the '{key}' key"),
+            NpyError::UnsupportedDtype(descr) => {
+                write!(f, "Only integer NPY arrays are supported. Found '{descr}'")
+            }
+            NpyError::UnsupportedShape(shape) => write!(
+                f,
+                "Only 2-dimensional NPY arrays are supported. Found shape {shape:?}"
+            ),
+            NpyError::PayloadLength { expected, found } => write!(
+                f,
+                "NPY payload should have {expected} elements, but has {found} elements"
+            ),
+            NpyError::ValueOutOfRange(value) => {
+                write!(f, "NPY value {value} does not fit in a 64-bit integer")
+            }
+        }
+    }
+}
+
+impl std::error::Error for NpyError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ByteOrder {
+    Little,
+    Big,
+}
+
+// The element type of the array, as described by the 'descr' key of the header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DType {
+    order: ByteOrder,
+    signed: bool,
+    size: usize,
+}
+
+// A 2-dimensional integer array, with its elements stored in row-major (C) order
+#[derive(Debug, PartialEq, Clone)]
+pub struct NpyArray {
+    pub rows: usize,
+    pub cols: usize,
+    pub data: Vec<i64>,
+}
+
+impl NpyArray {
+    pub fn get(&self, row: usize, col: usize) -> Option<i64> {
+        if row >= self.rows || col >= self.cols {
+            return None;
+        }
+        self.data.get(row * self.cols + col).copied()
+    }
+
+    // Serializes the array as a version 1.0 NPY file, with little-endian i64 elements in C order
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with_comment("")
+    }
+
+    // Serializes the array like `to_bytes`, with a comment after the header dict. NumPy ignores it, as the header is
+    // read as a Python literal. The comment is kept on a single line of printable ASCII, other characters are dropped.
+    pub fn to_bytes_with_comment(&self, comment: &str) -> Vec<u8> {
+        let comment: String = comment
+            .chars()
+            .filter(|c| c.is_ascii_graphic() || *c == ' ')
+            .collect();
+        let dict = format!(
+            "{{'descr': '<i8', 'fortran_order': False, 'shape': ({}, {}), }}",
+            self.rows, self.cols
+        );
+        let dict = match comment.trim() {
+            "" => dict,
+            comment => format!("{dict} # {comment}"),
+        };
+
+        // The header (magic, version, length, dict and trailing newline) is padded with spaces to a multiple of 64 bytes
+        let unpadded_len = MAGIC.len() + 2 + 2 + dict.len() + 1;
+        let padding = (64 - unpadded_len % 64) % 64;
+        let header = format!("{dict}{}\n", " ".repeat(padding));
+
+        let mut bytes = Vec::with_capacity(unpadded_len + padding + self.data.len() * 8);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&[1, 0]);
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        self.data
+            .iter()
+            .for_each(|value| bytes.extend_from_slice(&value.to_le_bytes()));
+        bytes
+    }
+}
+
+// The comment written after the header dict of a NPY file by `NpyArray::to_bytes_with_comment`, if any
+pub fn header_comment(bytes: &[u8]) -> Result<Option<String>, NpyError> {
+    let (header, _) = split_header(bytes)?;
+    Ok(header
+        .split_once('}')
+        .and_then(|(_, rest)| rest.split_once('#'))
+        .map(|(_, comment)| comment.trim().to_string())
+        .filter(|comment| !comment.is_empty()))
+}
+
+// Splits the raw contents of a NPY file into the header dict and the payload
+fn split_header(bytes: &[u8]) -> Result<(&str, &[u8]), NpyError> {
+    let Some(rest) = bytes.strip_prefix(MAGIC) else {
+        return Err(NpyError::NotNpy);
+    };
+
+    // The header length is a u16 in version 1.0, and a u32 in versions 2.0 and 3.0
+    let (header_len, rest) = match rest {
+        [1, _, len @ ..] if len.len() >= 2 => {
+            (u16::from_le_bytes([len[0], len[1]]) as usize, &len[2..])
+        }
+        [2 | 3, _, len @ ..] if len.len() >= 4 => (
+            u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize,
+            &len[4..],
+        ),
+        [1..=3, ..] => return Err(NpyError::TruncatedHeader),
+        [version, ..] => return Err(NpyError::UnsupportedVersion(*version)),
+        [] => return Err(NpyError::TruncatedHeader),
+    };
+
+    if rest.len() < header_len {
+        return Err(NpyError::TruncatedHeader);
+    }
+    let (header, payload) = rest.split_at(header_len);
+    let header = std::str::from_utf8(header).map_err(|_| NpyError::NotNpy)?;
+    Ok((header, payload))
+}
+
+impl TryFrom<&[u8]> for NpyArray {
+    type Error = NpyError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let (header, payload) = split_header(bytes)?;
+
+        let dtype = parse_descr(dict_value(header, "descr")?)?;
+        let fortran_order = match dict_value(header, "fortran_order")? {
+            "True" => true,
+            "False" => false,
+            _ => return Err(NpyError::MalformedHeader("fortran_order")),
+        };
+        let (rows, cols) = parse_shape(dict_value(header, "shape")?)?;
+
+        let count = rows
+            .checked_mul(cols)
+            .ok_or(NpyError::MalformedHeader("shape"))?;
+        let size = count
+            .checked_mul(dtype.size)
+            .ok_or(NpyError::MalformedHeader("shape"))?;
+        if payload.len() != size {
+            return Err(NpyError::PayloadLength {
+                expected: count,
+                found: payload.len() / dtype.size,
+            });
+        }
+
+        let values = payload
+            .chunks_exact(dtype.size)
+            .map(|chunk| read_int(chunk, dtype))
+            .collect::<Result<Vec<i64>, _>>()?;
+
+        // Fortran-ordered arrays are stored column by column, so they are transposed into row-major order
+        let data = if fortran_order {
+            (0..count)
+                .map(|idx| values[(idx % cols) * rows + idx / cols])
+                .collect()
+        } else {
+            values
+        };
+
+        Ok(NpyArray { rows, cols, data })
+    }
+}
+
+// Finds the raw (trimmed) value associated to a key in the header's Python dict literal
+fn dict_value<'a>(header: &'a str, key: &'static str) -> Result<&'a str, NpyError> {
+    let key_start = [format!("'{key}'"), format!("\"{key}\"")]
+        .iter()
+        .find_map(|quoted| header.find(quoted.as_str()).map(|idx| idx + quoted.len()))
+        .ok_or(NpyError::MissingKey(key))?;
+
+    let value = header[key_start..]
+        .trim_start()
+        .strip_prefix(':')
+        .ok_or(NpyError::MalformedHeader(key))?
+        .trim_start();
+
+    // The value ends at the first comma or closing brace outside of a tuple
+    let mut depth = 0usize;
+    let end = value
+        .char_indices()
+        .find(|(_, c)| match c {
+            '(' => {
+                depth += 1;
+                false
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                false
+            }
+            ',' | '}' => depth == 0,
+            _ => false,
+        })
+        .map(|(idx, _)| idx)
+        .ok_or(NpyError::MalformedHeader(key))?;
+
+    Ok(value[..end].trim())
+}
+
+fn parse_descr(value: &str) -> Result<DType, NpyError> {
+    let descr = value
+        .strip_prefix('\'')
+        .and_then(|v| v.strip_suffix('\''))
+        .or_else(|| value.strip_prefix('"').and_then(|v| v.strip_suffix('"')))
+        .ok_or(NpyError::MalformedHeader("descr"))?;
+    let unsupported = || NpyError::UnsupportedDtype(descr.to_string());
+
+    let mut chars = descr.chars();
+    let order = match chars.next() {
+        Some('<') | Some('|') => ByteOrder::Little,
+        Some('>') => ByteOrder::Big,
+        Some('=') if cfg!(target_endian = "big") => ByteOrder::Big,
+        Some('=') => ByteOrder::Little,
+        _ => return Err(unsupported()),
+    };
+    let signed = match chars.next() {
+        Some('i') => true,
+        Some('u') => false,
+        _ => return Err(unsupported()),
+    };
+    let size = match chars.as_str() {
+        "1" => 1,
+        "2" => 2,
+        "4" => 4,
+        "8" => 8,
+        _ => return Err(unsupported()),
+    };
+
+    Ok(DType {
+        order,
+        signed,
+        size,
+    })
+}
+
+fn parse_shape(value: &str) -> Result<(usize, usize), NpyError> {
+    let dims = value
+        .strip_prefix('(')
+        .and_then(|v| v.strip_suffix(')'))
+        .ok_or(NpyError::MalformedHeader("shape"))?
+        .split(',')
+        .map(str::trim)
+        .filter(|dim| !dim.is_empty())
+        .map(|dim| dim.parse::<usize>())
+        .collect::<Result<Vec<usize>, _>>()
+        .map_err(|_| NpyError::MalformedHeader("shape"))?;
+
+    match dims.as_slice() {
+        [rows, cols] => Ok((*rows, *cols)),
+        _ => Err(NpyError::UnsupportedShape(dims)),
+    }
+}
+
+// Reads a single integer of the given type, widening it to an i64
+fn read_int(bytes: &[u8], dtype: DType) -> Result<i64, NpyError> {
+    let mut buffer = [0u8; 8];
+    match dtype.order {
+        ByteOrder::Little => buffer[..dtype.size].copy_from_slice(bytes),
+        ByteOrder::Big => bytes
+            .iter()
+            .rev()
+            .zip(buffer.iter_mut())
+            .for_each(|(byte, slot)| *slot = *byte),
+    }
+    let raw = u64::from_le_bytes(buffer);
+
+    let bits = dtype.size * 8;
+    if dtype.signed {
+        // Sign-extend the value from its original width
+        let shift = 64 - bits;
+        Ok(((raw << shift) as i64) >> shift)
+    } else {
+        i64::try_from(raw).map_err(|_| NpyError::ValueOutOfRange(raw))
+    }
+}
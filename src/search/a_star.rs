@@ -1,80 +1,218 @@
-use std::{collections::VecDeque, rc::Rc};
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap},
+    rc::Rc,
+};
 
-use crate::maze::Maze;
+use crate::maze::{Coordinates, Cost, Maze};
 
-use super::{path::Path, MazeNode, Searcher};
-
-// Type-alias "cost" for better semantics
-type Cost = u64;
+use super::{
+    path::{Path, PathArena, PathIdx},
+    MazeNode, SearchMode, Searcher,
+};
 
 // Fn(current_node, end_node) -> heuristical_cost;
 pub trait HeuristicFn: Fn(&MazeNode, &MazeNode) -> Cost {}
 impl<T> HeuristicFn for T where T: Fn(&MazeNode, &MazeNode) -> Cost {}
 
-// The A* searcher will store a vector of tuples, each with a path, the cost of the path, and the predicted cost from the last node to the end node.
-pub struct AStarSearcher<F>(Rc<Maze>, VecDeque<(Path, Cost, Cost)>, Box<F>)
+// An entry of the A* frontier: the index of a path in the arena, the cost of the path, the predicted cost from the
+// last node to the end node, and the order in which the entry was inserted in the frontier.
+struct FrontierEntry {
+    path: PathIdx,
+    cost: Cost,
+    heuristic: Cost,
+    order: u64,
+}
+
+impl FrontierEntry {
+    // Entries are prioritized by lowest cost + heuristic, then lowest heuristic, then earliest insertion.
+    // The key is reversed, as the BinaryHeap pops the greatest entry first. The sum saturates, as heavily weighted
+    // heuristics can get close to Cost::MAX.
+    fn priority(&self) -> Reverse<(Cost, Cost, u64)> {
+        Reverse((
+            self.cost.saturating_add(self.heuristic),
+            self.heuristic,
+            self.order,
+        ))
+    }
+}
+
+impl PartialEq for FrontierEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority() == other.priority()
+    }
+}
+
+impl Eq for FrontierEntry {}
+
+impl PartialOrd for FrontierEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FrontierEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority().cmp(&other.priority())
+    }
+}
+
+// The A* searcher will store an arena of all the paths developed so far, and a priority queue of frontier entries, with the most promising entry on top.
+// In graph mode, it also stores the best known cost to reach each cell, so that paths reaching a cell through a more expensive route are dropped.
+pub struct AStarSearcher<F>
 where
-    F: HeuristicFn;
+    F: HeuristicFn,
+{
+    maze: Rc<Maze>,
+    arena: PathArena,
+    frontier: BinaryHeap<FrontierEntry>,
+    heuristic: Box<F>,
+    mode: SearchMode,
+    best_costs: HashMap<Coordinates, Cost>,
+    insertions: u64,
+    // The factor applied to the heuristic, 1 for plain A*
+    epsilon: f64,
+}
 
 impl<F: HeuristicFn> AStarSearcher<F> {
     pub fn new(maze: Rc<Maze>, heuristic: Box<F>) -> AStarSearcher<F> {
+        AStarSearcher::with_mode(maze, heuristic, SearchMode::default())
+    }
+
+    pub fn with_mode(maze: Rc<Maze>, heuristic: Box<F>, mode: SearchMode) -> AStarSearcher<F> {
+        AStarSearcher::with_epsilon(maze, heuristic, mode, 1.)
+    }
+
+    // Builds a searcher ordering paths by cost + epsilon * heuristic, as used by weighted A*
+    pub(super) fn with_epsilon(
+        maze: Rc<Maze>,
+        heuristic: Box<F>,
+        mode: SearchMode,
+        epsilon: f64,
+    ) -> AStarSearcher<F> {
         let start_node = maze.get_start();
+        let start_coord = start_node.get_coordinates();
 
-        let mut initial_path: Path = Path::new();
-        initial_path.push(start_node.clone());
+        let mut arena = PathArena::new();
+        let initial_path = arena.push(start_node, None);
 
         let initial_path_length = 0 as Cost;
-        let starting_heuristic = heuristic(&start_node, &maze.get_end());
+        let best_costs = [(start_coord, initial_path_length)].into();
 
-        let initial_path_list = [(initial_path, initial_path_length, starting_heuristic)].into();
+        let mut searcher = AStarSearcher {
+            maze,
+            arena,
+            frontier: BinaryHeap::new(),
+            heuristic,
+            mode,
+            best_costs,
+            insertions: 0,
+            epsilon,
+        };
+        searcher.push(initial_path, initial_path_length);
+        searcher
+    }
 
-        AStarSearcher(maze, initial_path_list, heuristic)
+    // Adds a path to the frontier, computing the (weighted) heuristic of its last node.
+    fn push(&mut self, path: PathIdx, cost: Cost) {
+        let Some(last) = self.arena.node(path) else {
+            return;
+        };
+        let heuristic = (self.heuristic)(last, &self.maze.get_end());
+        let heuristic = (heuristic as f64 * self.epsilon) as Cost;
+
+        self.frontier.push(FrontierEntry {
+            path,
+            cost,
+            heuristic,
+            order: self.insertions,
+        });
+        self.insertions += 1;
+    }
+
+    // In graph mode, a path is outdated if a cheaper path to its last cell has been found since it was added.
+    fn is_outdated(&self, entry: &FrontierEntry) -> bool {
+        self.mode == SearchMode::Graph
+            && self
+                .arena
+                .node(entry.path)
+                .and_then(|node| self.best_costs.get(&node.get_coordinates()))
+                .is_some_and(|best_cost| *best_cost < entry.cost)
+    }
+
+    // Drops the outdated entries on top of the frontier, so that the top entry is always the next one to be developed.
+    fn discard_outdated(&mut self) {
+        while self
+            .frontier
+            .peek()
+            .is_some_and(|entry| self.is_outdated(entry))
+        {
+            self.frontier.pop();
+        }
     }
 }
 
 impl<F: HeuristicFn> super::Searcher for AStarSearcher<F> {
     // To get the current path, we will return the path with the lowest cost + heuristic.
-    fn get_current_path(&self) -> Option<&Path> {
-        self.1
-            .iter()
-            .min_by_key(|(_, cost, heuristic)| heuristic + cost)
-            .map(|(path, _, _)| path)
+    fn get_current_path(&self) -> Option<Path> {
+        self.frontier
+            .peek()
+            .map(|entry| self.arena.path(entry.path))
     }
 
     // To get the considered nodes, return the neighbours of the last node of each path.
     fn get_considered_nodes(&self) -> Vec<MazeNode> {
-        self.1
+        self.frontier
             .iter()
-            .filter_map(|(path, _, _)| (path.last().cloned()))
+            .filter_map(|entry| self.arena.node(entry.path).cloned())
             .flat_map(|node| node.get_neighbors())
             .collect()
     }
 
     // To develop the next node, we will take the path with the lowest cost + heuristic, and deepen it.
-    #[allow(clippy::expect_used)]
+    // The cost of each new path is the cost of the developed path plus the cost of stepping onto the new node.
+    // In graph mode, new paths are only kept if they improve the best known cost of their last cell.
     fn develop_next_node(&mut self) -> Option<MazeNode> {
-        let (idx, _) = self
-            .1
-            .iter()
-            .enumerate()
-            .min_by_key(|(_, (_, cost, heuristic))| heuristic + cost)?;
-        let Some((path, cost, _)) = self.1.remove(idx) else {
-            unreachable!("Validated index is out-of-bounds")
-        };
+        self.discard_outdated();
+        let FrontierEntry { path, cost, .. } = self.frontier.pop()?;
 
-        let node = path.last()?.clone();
+        let node = self.arena.node(path)?.clone();
 
-        let mut new_paths: VecDeque<(Path, Cost, Cost)> = path
-            .deepen_path()
-            .into_iter()
-            .map(|path| {
-                let node_heuristic =
-                    (self.2)(path.last().expect("Path is empty!"), &self.0.get_end());
-                (path, cost + 1, node_heuristic)
-            })
-            .collect();
+        match self.mode {
+            SearchMode::Graph => {
+                for neighbour in node.get_neighbors() {
+                    let Some(step_cost) = node.step_cost(&neighbour) else {
+                        continue;
+                    };
+                    let new_cost = cost + step_cost;
+                    let coord = neighbour.get_coordinates();
+                    if self
+                        .best_costs
+                        .get(&coord)
+                        .is_some_and(|best| *best <= new_cost)
+                    {
+                        continue;
+                    }
+                    self.best_costs.insert(coord, new_cost);
+                    let new_path = self.arena.push(neighbour, Some(path));
+                    self.push(new_path, new_cost);
+                }
+            }
+            SearchMode::Tree => {
+                for new_path in self.arena.deepen(path) {
+                    let Some(step_cost) = self
+                        .arena
+                        .node(new_path)
+                        .and_then(|neighbour| node.step_cost(neighbour))
+                    else {
+                        continue;
+                    };
+                    self.push(new_path, cost + step_cost);
+                }
+            }
+        }
 
-        self.1.append(&mut new_paths);
+        self.discard_outdated();
         Some(node)
     }
 }
@@ -86,3 +224,109 @@ impl<F: HeuristicFn> Iterator for AStarSearcher<F> {
         self.develop_next_node()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, rc::Rc};
+
+    use super::AStarSearcher;
+    use crate::maze::{Connectivity, CornerCutting, Maze};
+    use crate::search::{
+        bfs::BreadthFirstSearcher, dijkstra::DijkstraSearcher, heuristics, SearchMode,
+    };
+    use crate::test_utils::{load_example, solve, EXAMPLES};
+    use crate::tilemap::{Tile, TileMap};
+
+    // A room with no walls inside, where many routes of the same length lead to the end
+    const OPEN_ROOM: &str = "111111\n120001\n100001\n100031\n111111";
+
+    // A room where the shortest route goes through mud, and the cheapest one around it
+    const MUDDY_ROOM: &str = "1111111\n1244431\n1000001\n1111111";
+
+    #[test]
+    fn both_modes_match_bfs_on_examples() -> Result<(), Box<dyn Error>> {
+        for example in EXAMPLES {
+            let maze = Rc::new(Maze::try_from(load_example(example)?)?);
+            let bfs =
+                solve(&mut BreadthFirstSearcher::new(&maze)).ok_or("BFS should find a path")?;
+            for mode in [SearchMode::Graph, SearchMode::Tree] {
+                let a_star = solve(&mut AStarSearcher::with_mode(
+                    maze.clone(),
+                    Box::new(heuristics::manhattan),
+                    mode,
+                ))
+                .ok_or(format!("{example} {mode:?}: A* should find a path"))?;
+                assert_eq!(a_star.len(), bfs.len(), "{example} {mode:?}");
+                assert_eq!(a_star.cost(), bfs.cost(), "{example} {mode:?}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn both_modes_find_the_cheapest_path_through_mud() -> Result<(), Box<dyn Error>> {
+        let maze = Rc::new(Maze::try_from(TileMap::try_from(String::from(
+            MUDDY_ROOM,
+        ))?)?);
+        let shortest =
+            solve(&mut BreadthFirstSearcher::new(&maze)).ok_or("BFS should find a path")?;
+        for mode in [SearchMode::Graph, SearchMode::Tree] {
+            let dijkstra = solve(&mut DijkstraSearcher::with_mode(maze.clone(), mode))
+                .ok_or("Dijkstra should find a path")?;
+            let a_star = solve(&mut AStarSearcher::with_mode(
+                maze.clone(),
+                Box::new(heuristics::manhattan),
+                mode,
+            ))
+            .ok_or("A* should find a path")?;
+            assert!(dijkstra.cost() < shortest.cost(), "{mode:?}");
+            assert!(dijkstra.len() > shortest.len(), "{mode:?}");
+            assert_eq!(a_star.cost(), dijkstra.cost(), "{mode:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn matches_bfs_with_diagonal_moves_on_examples() -> Result<(), Box<dyn Error>> {
+        for example in EXAMPLES {
+            for corner_cutting in [CornerCutting::Never, CornerCutting::Always] {
+                let connectivity = Connectivity::Eight(corner_cutting);
+                let maze = Rc::new(
+                    Maze::try_from(load_example(example)?)?.with_connectivity(connectivity),
+                );
+                let bfs =
+                    solve(&mut BreadthFirstSearcher::new(&maze)).ok_or("BFS should find a path")?;
+                let a_star = solve(&mut AStarSearcher::new(
+                    maze.clone(),
+                    Box::new(heuristics::octile),
+                ))
+                .ok_or(format!("{example} {connectivity:?}: A* should find a path"))?;
+                assert_eq!(a_star.cost(), bfs.cost(), "{example} {connectivity:?}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn ties_are_broken_towards_the_end_and_the_same_way_every_time() -> Result<(), Box<dyn Error>> {
+        let maze = Rc::new(Maze::try_from(TileMap::try_from(String::from(OPEN_ROOM))?)?);
+        let developed = || {
+            AStarSearcher::new(maze.clone(), Box::new(heuristics::manhattan))
+                .scan(false, |found, node| {
+                    (!*found).then(|| {
+                        *found = node.get_tile() == Tile::End;
+                        node.get_coordinates()
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Every cell on a shortest route has the same cost + heuristic, and preferring the lowest heuristic goes
+        // straight to the end without developing any other cell
+        let first = developed();
+        assert_eq!(first.len(), 6);
+        assert_eq!(first.last(), Some(&maze.get_end().get_coordinates()));
+        assert_eq!(developed(), first);
+        Ok(())
+    }
+}
@@ -0,0 +1,343 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    rc::Rc,
+};
+
+use crate::maze::{Coordinates, Cost, Maze};
+
+use super::{
+    a_star::HeuristicFn,
+    path::{Path, PathArena, PathIdx},
+    MazeNode, Searcher,
+};
+
+// The amount epsilon is lowered by between two passes, unless another one is given
+pub const DEFAULT_EPSILON_STEP: f64 = 0.5;
+
+// An entry of the open list: the priority (cost + epsilon * heuristic, heuristic, insertion order), the cell, and the
+// cost of the cell when the entry was inserted
+type OpenEntry = (Reverse<(Cost, Cost, u64)>, Coordinates, Cost);
+
+// The Anytime Repairing A* searcher runs weighted A* passes with a decreasing epsilon, reusing the work of the
+// previous passes instead of starting over. The first pass finds a solution quickly, and each following pass
+// publishes a better (or equal) solution along with a bound on its suboptimality, until that bound reaches 1.
+// During a pass, each cell is developed at most once: cells whose cost improves after being developed are set aside
+// (the "inconsistent" cells), and only put back in the open list at the start of the next pass.
+pub struct AraStarSearcher<F>
+where
+    F: HeuristicFn,
+{
+    maze: Rc<Maze>,
+    arena: PathArena,
+    heuristic: Box<F>,
+    epsilon: f64,
+    epsilon_step: f64,
+    // The best known cost of each cell reached so far, and the path reaching it for that cost
+    best: HashMap<Coordinates, (Cost, PathIdx)>,
+    open: BinaryHeap<OpenEntry>,
+    closed: HashSet<Coordinates>,
+    inconsistent: HashSet<Coordinates>,
+    solution: Option<Path>,
+    bound: Option<f64>,
+    iteration: usize,
+    insertions: u64,
+    // Whether the last solution is known to be optimal, and whether the end node has been yielded since
+    finished: bool,
+    done: bool,
+}
+
+impl<F: HeuristicFn> AraStarSearcher<F> {
+    pub fn new(maze: Rc<Maze>, heuristic: Box<F>, epsilon: f64) -> AraStarSearcher<F> {
+        AraStarSearcher::with_epsilon_step(maze, heuristic, epsilon, DEFAULT_EPSILON_STEP)
+    }
+
+    pub fn with_epsilon_step(
+        maze: Rc<Maze>,
+        heuristic: Box<F>,
+        epsilon: f64,
+        epsilon_step: f64,
+    ) -> AraStarSearcher<F> {
+        let start_node = maze.get_start();
+        let start_coord = start_node.get_coordinates();
+
+        let mut arena = PathArena::new();
+        let initial_path = arena.push(start_node, None);
+
+        let mut searcher = AraStarSearcher {
+            maze,
+            arena,
+            heuristic,
+            epsilon: epsilon.max(1.),
+            epsilon_step,
+            best: [(start_coord, (0, initial_path))].into(),
+            open: BinaryHeap::new(),
+            closed: HashSet::new(),
+            inconsistent: HashSet::new(),
+            solution: None,
+            bound: None,
+            iteration: 0,
+            insertions: 0,
+            finished: false,
+            done: false,
+        };
+        searcher.push(start_coord, 0);
+        if searcher.is_pass_over() {
+            searcher.end_pass();
+        }
+        searcher
+    }
+
+    fn heuristic(&self, coord: Coordinates) -> Cost {
+        let (Some(node), end_node) = (self.maze.get_node(coord), self.maze.get_end()) else {
+            return 0;
+        };
+        (self.heuristic)(&node, &end_node)
+    }
+
+    // The priority of a cell in the current pass: cost + epsilon * heuristic. The sum saturates, as the weighted
+    // heuristic can get close to Cost::MAX.
+    fn key(&self, coord: Coordinates, cost: Cost) -> (Cost, Cost) {
+        let heuristic = (self.heuristic(coord) as f64 * self.epsilon) as Cost;
+        (cost.saturating_add(heuristic), heuristic)
+    }
+
+    // Adds a cell to the open list, with the priority given by the current epsilon.
+    fn push(&mut self, coord: Coordinates, cost: Cost) {
+        let (priority, heuristic) = self.key(coord, cost);
+        self.open
+            .push((Reverse((priority, heuristic, self.insertions)), coord, cost));
+        self.insertions += 1;
+    }
+
+    // An entry is outdated if its cell was developed in this pass, or if a cheaper path to it has been found since.
+    fn is_outdated(&self, (_, coord, cost): &OpenEntry) -> bool {
+        self.closed.contains(coord) || self.best.get(coord).is_some_and(|(best, _)| best < cost)
+    }
+
+    // Drops the outdated entries on top of the open list, so that the top entry is always the next one to be developed.
+    fn discard_outdated(&mut self) {
+        while self
+            .open
+            .peek()
+            .is_some_and(|entry| self.is_outdated(entry))
+        {
+            self.open.pop();
+        }
+    }
+
+    // The cells still waiting in the open list
+    fn open_cells(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.open
+            .iter()
+            .filter(|entry| !self.is_outdated(entry))
+            .map(|(_, coord, _)| *coord)
+    }
+
+    // A pass is over once no cell in the open list can lead to a path cheaper than the current solution (according
+    // to the weighted priority), or once the open list is empty.
+    fn is_pass_over(&self) -> bool {
+        let end_coord = self.maze.get_end().get_coordinates();
+        let Some((top_priority, _, _)) = self.open.peek().map(|(Reverse(key), _, _)| *key) else {
+            return true;
+        };
+        self.best
+            .get(&end_coord)
+            .is_some_and(|(cost, _)| self.key(end_coord, *cost).0 <= top_priority)
+    }
+
+    // Publishes the solution of the pass and its suboptimality bound, then starts the next pass with a lower epsilon,
+    // unless the solution is already known to be optimal.
+    fn end_pass(&mut self) {
+        let end_coord = self.maze.get_end().get_coordinates();
+        let Some((solution_cost, solution_path)) = self.best.get(&end_coord).copied() else {
+            return;
+        };
+        self.solution = Some(self.arena.path(solution_path));
+
+        // No path can be cheaper than the lowest cost + heuristic among the cells that may still be improved
+        let lower_bound = self
+            .open_cells()
+            .chain(self.inconsistent.iter().copied())
+            .filter_map(|coord| {
+                let (cost, _) = self.best.get(&coord)?;
+                Some(cost.saturating_add(self.heuristic(coord)))
+            })
+            .min();
+        let bound = match lower_bound {
+            None => 1.,
+            Some(0) => self.epsilon,
+            Some(lower_bound) => (solution_cost as f64 / lower_bound as f64).min(self.epsilon),
+        }
+        .max(1.);
+        self.bound = Some(bound);
+
+        if bound <= 1. {
+            self.finished = true;
+            return;
+        }
+
+        // Start the next pass: the open list is rebuilt with the new epsilon, and the inconsistent cells join it. An
+        // infinite epsilon would never come down by steps, so it drops to 1 at once.
+        self.epsilon = if self.epsilon_step > 0. && self.epsilon.is_finite() {
+            (self.epsilon - self.epsilon_step).max(1.)
+        } else {
+            1.
+        };
+        self.iteration += 1;
+
+        let mut cells: Vec<Coordinates> = self.open_cells().collect();
+        cells.extend(self.inconsistent.drain());
+        self.open.clear();
+        self.closed.clear();
+        for coord in cells {
+            if let Some((cost, _)) = self.best.get(&coord).copied() {
+                self.push(coord, cost);
+            }
+        }
+
+        self.discard_outdated();
+        if self.is_pass_over() {
+            self.end_pass();
+        }
+    }
+
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+}
+
+impl<F: HeuristicFn> super::Searcher for AraStarSearcher<F> {
+    // To get the current path, return the final solution once it is known to be optimal, or else the path to the
+    // next cell to be developed.
+    fn get_current_path(&self) -> Option<Path> {
+        if self.finished {
+            return self.solution.clone();
+        }
+        self.open.peek().and_then(|(_, coord, _)| {
+            let (_, path) = self.best.get(coord)?;
+            Some(self.arena.path(*path))
+        })
+    }
+
+    // To get the considered nodes, return the cells in the open list.
+    fn get_considered_nodes(&self) -> Vec<MazeNode> {
+        self.open_cells()
+            .filter_map(|coord| self.maze.get_node(coord))
+            .collect()
+    }
+
+    // To develop the next node, take the cell with the lowest priority and relax its neighbours. Neighbours already
+    // developed in this pass are set aside as inconsistent instead of being added back to the open list.
+    // Once the solution is known to be optimal, the end node is yielded to signal that the search is done.
+    fn develop_next_node(&mut self) -> Option<MazeNode> {
+        if self.done {
+            return None;
+        }
+        if self.finished {
+            self.done = true;
+            return Some(self.maze.get_end());
+        }
+
+        self.discard_outdated();
+        let (_, coord, cost) = self.open.pop()?;
+        self.closed.insert(coord);
+
+        let (_, path) = self.best.get(&coord).copied()?;
+        let node = self.arena.node(path)?.clone();
+
+        for neighbour in node.get_neighbors() {
+            let Some(step_cost) = node.step_cost(&neighbour) else {
+                continue;
+            };
+            let new_cost = cost + step_cost;
+            let neighbour_coord = neighbour.get_coordinates();
+            if self
+                .best
+                .get(&neighbour_coord)
+                .is_some_and(|(best, _)| *best <= new_cost)
+            {
+                continue;
+            }
+
+            let new_path = self.arena.push(neighbour, Some(path));
+            self.best.insert(neighbour_coord, (new_cost, new_path));
+            if self.closed.contains(&neighbour_coord) {
+                self.inconsistent.insert(neighbour_coord);
+            } else {
+                self.push(neighbour_coord, new_cost);
+            }
+        }
+
+        self.discard_outdated();
+        if self.is_pass_over() {
+            self.end_pass();
+        }
+        Some(node)
+    }
+
+    fn get_iteration(&self) -> Option<usize> {
+        Some(self.iteration)
+    }
+
+    fn get_suboptimality_bound(&self) -> Option<f64> {
+        self.bound
+    }
+
+    fn get_solution(&self) -> Option<Path> {
+        self.solution.clone()
+    }
+}
+
+impl<F: HeuristicFn> Iterator for AraStarSearcher<F> {
+    type Item = MazeNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.develop_next_node()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, rc::Rc};
+
+    use super::AraStarSearcher;
+    use crate::maze::Maze;
+    use crate::search::{dijkstra::DijkstraSearcher, heuristics, Searcher};
+    use crate::test_utils::{load_example, solve, EXAMPLES};
+
+    #[test]
+    fn converges_to_the_optimal_solution_within_the_bounds() -> Result<(), Box<dyn Error>> {
+        for example in EXAMPLES {
+            let maze = Rc::new(Maze::try_from(load_example(example)?)?);
+            let optimal = solve(&mut DijkstraSearcher::new(maze.clone()))
+                .and_then(|path| path.cost())
+                .ok_or("Dijkstra should find a path")?;
+
+            for epsilon in [1., 2.5, 10., f64::INFINITY] {
+                let mut searcher =
+                    AraStarSearcher::new(maze.clone(), Box::new(heuristics::manhattan), epsilon);
+                let mut solution_cost = None;
+                while searcher.next().is_some() {
+                    let Some(cost) = searcher.get_solution().and_then(|path| path.cost()) else {
+                        continue;
+                    };
+                    let bound = searcher.get_suboptimality_bound().ok_or("no bound")?;
+                    assert!(
+                        cost as f64 <= bound * optimal as f64,
+                        "{example} with epsilon {epsilon}: {cost} > {bound} * {optimal}"
+                    );
+                    assert!(solution_cost.is_none_or(|previous| cost <= previous));
+                    solution_cost = Some(cost);
+                }
+                assert_eq!(
+                    solution_cost,
+                    Some(optimal),
+                    "{example} with epsilon {epsilon}"
+                );
+                assert_eq!(searcher.get_suboptimality_bound(), Some(1.));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+use std::{collections::HashSet, collections::VecDeque, rc::Rc};
+
+use crate::maze::{Coordinates, Cost, Maze};
+
+use super::{
+    a_star::HeuristicFn,
+    path::{Path, PathArena, PathIdx},
+    MazeNode, SearchFailure, Searcher,
+};
+
+// A path waiting to enter the next beam: the heuristic of its last node, its cost, its insertion order, and its index
+type Candidate = (Cost, Cost, u64, PathIdx);
+
+// The Beam searcher develops the maze one depth at a time, like a breadth-first search, but only keeps the `width`
+// most promising paths of each depth (by lowest heuristic, then lowest cost), so memory stays bounded by the width.
+// The other paths are pruned, which makes the search incomplete: it may fail to find a path even though one exists,
+// and the path found is not necessarily the shortest one.
+pub struct BeamSearcher<F>
+where
+    F: HeuristicFn,
+{
+    maze: Rc<Maze>,
+    arena: PathArena,
+    heuristic: Box<F>,
+    width: usize,
+    // The paths of the current depth still to be developed, with their cost
+    beam: VecDeque<(PathIdx, Cost)>,
+    // The paths of the next depth found so far
+    candidates: Vec<Candidate>,
+    // The cells that already entered a beam, which are never developed again
+    visited: HashSet<Coordinates>,
+    // The cells whose paths were all dropped because the beam was full, and that never entered a beam since
+    dropped: HashSet<Coordinates>,
+    depth: usize,
+    insertions: u64,
+}
+
+impl<F: HeuristicFn> BeamSearcher<F> {
+    // A beam must hold at least one path, so a width of 0 is treated as 1. Callers that take the width from users
+    // should reject 0 instead.
+    pub fn new(maze: Rc<Maze>, heuristic: Box<F>, width: usize) -> BeamSearcher<F> {
+        let start_node = maze.get_start();
+        let visited = [start_node.get_coordinates()].into();
+
+        let mut arena = PathArena::new();
+        let initial_path = arena.push(start_node, None);
+
+        BeamSearcher {
+            maze,
+            arena,
+            heuristic,
+            width: width.max(1),
+            beam: [(initial_path, 0)].into(),
+            candidates: Vec::new(),
+            visited,
+            dropped: HashSet::new(),
+            depth: 0,
+            insertions: 0,
+        }
+    }
+
+    // Moves on to the next depth: the candidates are sorted from most to least promising, only the best path to each
+    // cell is kept, and only the first `width` of them enter the beam.
+    fn next_depth(&mut self) {
+        let mut candidates = std::mem::take(&mut self.candidates);
+        candidates.sort_unstable();
+
+        for (_, cost, _, path) in candidates {
+            let Some(coord) = self.arena.node(path).map(MazeNode::get_coordinates) else {
+                continue;
+            };
+            if self.visited.contains(&coord) {
+                continue;
+            }
+            if self.beam.len() == self.width {
+                self.dropped.insert(coord);
+                continue;
+            }
+            self.dropped.remove(&coord);
+            self.visited.insert(coord);
+            self.beam.push_back((path, cost));
+        }
+        self.depth += 1;
+    }
+
+    // The number of depths developed so far
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    // The number of cells whose paths were all dropped because the beam was full, and that never entered a beam since
+    pub fn pruned(&self) -> usize {
+        self.dropped.len()
+    }
+
+    // Whether a dropped cell could have led somewhere: it is the end node, or it has neighbours that never entered a
+    // beam. Dropping a cell whose neighbours were all developed lost nothing.
+    fn could_continue(&self, coord: Coordinates) -> bool {
+        let Some(node) = self.maze.get_node(coord) else {
+            return false;
+        };
+        node == self.maze.get_end()
+            || node.get_neighbors().into_iter().any(|neighbour| {
+                node.step_cost(&neighbour).is_some()
+                    && !self.visited.contains(&neighbour.get_coordinates())
+            })
+    }
+}
+
+impl<F: HeuristicFn> super::Searcher for BeamSearcher<F> {
+    // To get the current path, return the next path of the beam.
+    fn get_current_path(&self) -> Option<Path> {
+        self.beam.front().map(|(path, _)| self.arena.path(*path))
+    }
+
+    // To get the considered nodes, return the last node of the paths in the beam and of the candidates for the next one.
+    fn get_considered_nodes(&self) -> Vec<MazeNode> {
+        self.beam
+            .iter()
+            .map(|(path, _)| *path)
+            .chain(self.candidates.iter().map(|(_, _, _, path)| *path))
+            .filter_map(|path| self.arena.node(path).cloned())
+            .collect()
+    }
+
+    // To develop the next node, take the next path of the beam, and add one candidate for each neighbour of its last
+    // node that never entered a beam. Once the beam is empty, the best candidates form the next one.
+    fn develop_next_node(&mut self) -> Option<MazeNode> {
+        let (path, cost) = self.beam.pop_front()?;
+        let node = self.arena.node(path)?.clone();
+        let end_node = self.maze.get_end();
+
+        for neighbour in node.get_neighbors() {
+            let Some(step_cost) = node.step_cost(&neighbour) else {
+                continue;
+            };
+            if self.visited.contains(&neighbour.get_coordinates()) {
+                continue;
+            }
+            let heuristic = (self.heuristic)(&neighbour, &end_node);
+            let new_path = self.arena.push(neighbour, Some(path));
+            self.candidates
+                .push((heuristic, cost + step_cost, self.insertions, new_path));
+            self.insertions += 1;
+        }
+
+        if self.beam.is_empty() {
+            self.next_depth();
+        }
+        Some(node)
+    }
+
+    // The search only fails because of the beam if a dropped cell could have led somewhere: otherwise, there is no
+    // path at all.
+    fn get_failure(&self) -> Option<SearchFailure> {
+        let pruning_failed =
+            self.beam.is_empty() && self.dropped.iter().any(|coord| self.could_continue(*coord));
+        pruning_failed.then_some(SearchFailure::Pruned {
+            pruned: self.pruned(),
+            width: self.width,
+        })
+    }
+}
+
+impl<F: HeuristicFn> Iterator for BeamSearcher<F> {
+    type Item = MazeNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.develop_next_node()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, rc::Rc};
+
+    use super::BeamSearcher;
+    use crate::maze::Maze;
+    use crate::search::{bfs::BreadthFirstSearcher, heuristics, SearchFailure, Searcher};
+    use crate::test_utils::{load_example, solve, EXAMPLES};
+    use crate::tilemap::TileMap;
+
+    // The start node has two neighbours: the one closer to the end node leads to a dead end, and the other one goes
+    // around the walls to the end node
+    const DETOUR: &str = "1111111\n1310021\n1011101\n1000001\n1111111";
+    // The end node cannot be reached, and the start node has a neighbour that leads nowhere
+    const WALLED_OFF: &str = "1111111\n1020001\n1111111\n1000031\n1111111";
+
+    fn maze(text: &str) -> Result<Rc<Maze>, Box<dyn Error>> {
+        Ok(Rc::new(Maze::try_from(TileMap::try_from(
+            text.to_string(),
+        )?)?))
+    }
+
+    #[test]
+    fn wide_beam_matches_bfs_on_examples() -> Result<(), Box<dyn Error>> {
+        for example in EXAMPLES {
+            let maze = Rc::new(Maze::try_from(load_example(example)?)?);
+            let width = maze.width() * maze.height();
+            let bfs = solve(&mut BreadthFirstSearcher::new(&maze)).map(|path| path.len());
+            let mut beam = BeamSearcher::new(maze, Box::new(heuristics::manhattan), width);
+            assert_eq!(solve(&mut beam).map(|path| path.len()), bfs, "{example}");
+            assert_eq!(beam.pruned(), 0, "{example}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn narrow_beam_reports_pruning() -> Result<(), Box<dyn Error>> {
+        let maze = maze(DETOUR)?;
+        let mut beam = BeamSearcher::new(maze.clone(), Box::new(heuristics::manhattan), 1);
+        assert!(solve(&mut beam).is_none());
+        assert_eq!(
+            beam.get_failure(),
+            Some(SearchFailure::Pruned {
+                pruned: 1,
+                width: 1
+            })
+        );
+
+        let mut beam = BeamSearcher::new(maze, Box::new(heuristics::manhattan), 2);
+        assert!(solve(&mut beam).is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn width_0_is_treated_as_1() -> Result<(), Box<dyn Error>> {
+        let mut beam = BeamSearcher::new(maze(DETOUR)?, Box::new(heuristics::manhattan), 0);
+        assert!(solve(&mut beam).is_none());
+        assert!(matches!(
+            beam.get_failure(),
+            Some(SearchFailure::Pruned { width: 1, .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn no_pruning_failure_without_a_path() -> Result<(), Box<dyn Error>> {
+        let mut beam = BeamSearcher::new(maze(WALLED_OFF)?, Box::new(heuristics::manhattan), 1);
+        assert!(solve(&mut beam).is_none());
+        assert_eq!(beam.pruned(), 1);
+        assert_eq!(beam.get_failure(), None);
+        Ok(())
+    }
+}
@@ -1,42 +1,76 @@
-use std::{collections::VecDeque, rc::Rc};
+use std::{
+    collections::{HashSet, VecDeque},
+    rc::Rc,
+};
 
-use crate::maze::Maze;
+use crate::maze::{Coordinates, Maze};
 
-use super::{path::Path, Searcher};
+use super::{
+    path::{Path, PathArena, PathIdx},
+    SearchMode, Searcher,
+};
 
-// The Breadth-First Searcher object stores a VecDeque, ideal for popping from start, of all the paths that are being considered.
-pub struct BreadthFirstSearcher(VecDeque<Path>);
+// The Breadth-First Searcher object stores an arena of all the paths developed so far, and a VecDeque, ideal for popping from start, with the indices of the paths that are being considered.
+// In graph mode, it also stores every cell that has already been reached, so that no cell is enqueued twice.
+pub struct BreadthFirstSearcher {
+    arena: PathArena,
+    queue: VecDeque<PathIdx>,
+    mode: SearchMode,
+    visited: HashSet<Coordinates>,
+}
 
 impl BreadthFirstSearcher {
     pub fn new(maze: &Rc<Maze>) -> BreadthFirstSearcher {
-        let mut initial_path = Path::new();
-        initial_path.push(maze.get_start());
-        BreadthFirstSearcher([initial_path].into())
+        BreadthFirstSearcher::with_mode(maze, SearchMode::default())
+    }
+
+    pub fn with_mode(maze: &Rc<Maze>, mode: SearchMode) -> BreadthFirstSearcher {
+        let start_node = maze.get_start();
+        let visited = [start_node.get_coordinates()].into();
+
+        let mut arena = PathArena::new();
+        let initial_path = arena.push(start_node, None);
+        BreadthFirstSearcher {
+            arena,
+            queue: [initial_path].into(),
+            mode,
+            visited,
+        }
     }
 }
 
 impl super::Searcher for BreadthFirstSearcher {
-    // To get the current path, return the first path in the vector.
-    fn get_current_path(&self) -> Option<&Path> {
-        self.0.front()
+    // To get the current path, return the first path in the queue.
+    fn get_current_path(&self) -> Option<Path> {
+        self.queue.front().map(|idx| self.arena.path(*idx))
     }
 
     // To get the considered nodes, return the neighbours of the tail of each path.
     fn get_considered_nodes(&self) -> Vec<crate::maze::MazeNode> {
-        self.0
+        self.queue
             .iter()
-            .filter_map(|path| path.last().cloned())
+            .filter_map(|idx| self.arena.node(*idx).cloned())
             .flat_map(|node| node.get_neighbors())
             .collect()
     }
 
-    // To develop the next node, pop the first path from the vector and deepen it.
+    // To develop the next node, pop the first path from the queue and deepen it.
+    // In graph mode, only the neighbours that were never reached before are enqueued.
     fn develop_next_node(&mut self) -> Option<crate::maze::MazeNode> {
-        let path = self.0.pop_front()?.to_owned();
-        let node = path.last()?.clone();
+        let idx = self.queue.pop_front()?;
+        let node = self.arena.node(idx)?.clone();
 
-        let mut new_paths = path.deepen_path().into_iter().collect::<VecDeque<Path>>();
-        self.0.append(&mut new_paths);
+        match self.mode {
+            SearchMode::Graph => {
+                for neighbour in node.get_neighbors() {
+                    if self.visited.insert(neighbour.get_coordinates()) {
+                        let new_idx = self.arena.push(neighbour, Some(idx));
+                        self.queue.push_back(new_idx);
+                    }
+                }
+            }
+            SearchMode::Tree => self.queue.extend(self.arena.deepen(idx)),
+        }
         Some(node)
     }
 }
@@ -48,3 +82,72 @@ impl Iterator for BreadthFirstSearcher {
         self.develop_next_node()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, error::Error, rc::Rc};
+
+    use super::BreadthFirstSearcher;
+    use crate::maze::Maze;
+    use crate::search::{dijkstra::DijkstraSearcher, SearchMode, Searcher};
+    use crate::test_utils::{load_example, solve, EXAMPLES};
+    use crate::tilemap::{Tile, TileMap};
+
+    // A room with no walls inside, where many routes of the same length lead to every cell
+    const OPEN_ROOM: &str = "111111\n120001\n100001\n100031\n111111";
+
+    // The coordinates of the nodes developed until the end node, in order
+    fn developed(searcher: &mut dyn Searcher) -> Vec<(usize, usize)> {
+        let mut developed = Vec::new();
+        for node in &mut *searcher {
+            developed.push(node.get_coordinates());
+            if node.get_tile() == Tile::End {
+                break;
+            }
+        }
+        developed
+    }
+
+    #[test]
+    fn both_modes_match_dijkstra_on_examples() -> Result<(), Box<dyn Error>> {
+        for example in EXAMPLES {
+            let maze = Rc::new(Maze::try_from(load_example(example)?)?);
+            let dijkstra = solve(&mut DijkstraSearcher::new(maze.clone()))
+                .ok_or("Dijkstra should find a path")?;
+            for mode in [SearchMode::Graph, SearchMode::Tree] {
+                let bfs = solve(&mut BreadthFirstSearcher::with_mode(&maze, mode))
+                    .ok_or(format!("{example} {mode:?}: BFS should find a path"))?;
+                // The bundled mazes have no mud or water, so the fewest steps are also the cheapest
+                assert_eq!(bfs.len(), dijkstra.len(), "{example} {mode:?}");
+                assert_eq!(bfs.cost(), dijkstra.cost(), "{example} {mode:?}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn only_tree_mode_develops_cells_twice() -> Result<(), Box<dyn Error>> {
+        let maze = Rc::new(Maze::try_from(TileMap::try_from(String::from(OPEN_ROOM))?)?);
+
+        let graph = developed(&mut BreadthFirstSearcher::with_mode(
+            &maze,
+            SearchMode::Graph,
+        ));
+        let distinct: HashSet<_> = graph.iter().collect();
+        assert_eq!(distinct.len(), graph.len());
+
+        let tree = developed(&mut BreadthFirstSearcher::with_mode(
+            &maze,
+            SearchMode::Tree,
+        ));
+        let distinct: HashSet<_> = tree.iter().collect();
+        assert!(distinct.len() < tree.len());
+
+        for mode in [SearchMode::Graph, SearchMode::Tree] {
+            let path = solve(&mut BreadthFirstSearcher::with_mode(&maze, mode))
+                .ok_or("BFS should find a path")?;
+            assert_eq!(path.len(), 6, "{mode:?}");
+        }
+        Ok(())
+    }
+}
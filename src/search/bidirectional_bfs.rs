@@ -0,0 +1,260 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+};
+
+use crate::maze::{Coordinates, Maze};
+
+use super::{
+    path::{Path, PathArena, PathIdx},
+    MazeNode, Searcher,
+};
+
+// The two directions the search grows from: forward from the start node, and backward from the end node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Forward,
+    Backward,
+}
+
+// One of the two searches: the paths developed so far (rooted at the start or at the end node), the queue of path
+// indices still to be developed (with their depth), and the path reaching each cell found so far.
+struct Frontier {
+    arena: PathArena,
+    queue: VecDeque<(PathIdx, usize)>,
+    reached: HashMap<Coordinates, PathIdx>,
+}
+
+impl Frontier {
+    fn new(root: MazeNode) -> Frontier {
+        let mut arena = PathArena::new();
+        let coord = root.get_coordinates();
+        let idx = arena.push(root, None);
+        Frontier {
+            arena,
+            queue: [(idx, 0)].into(),
+            reached: [(coord, idx)].into(),
+        }
+    }
+
+    // Pops the next path from the queue, and enqueues one new path for each neighbour never reached before.
+    // Returns the developed node, and the new paths that reached a cell already reached by the other frontier.
+    fn develop(&mut self, other: &Frontier) -> Option<(MazeNode, Vec<(PathIdx, PathIdx)>)> {
+        let (idx, depth) = self.queue.pop_front()?;
+        let node = self.arena.node(idx)?.clone();
+
+        let mut meetings = Vec::new();
+        for neighbour in node.get_neighbors() {
+            let coord = neighbour.get_coordinates();
+            if self.reached.contains_key(&coord) {
+                continue;
+            }
+            let new_idx = self.arena.push(neighbour, Some(idx));
+            self.reached.insert(coord, new_idx);
+            self.queue.push_back((new_idx, depth + 1));
+            if let Some(other_idx) = other.reached.get(&coord) {
+                meetings.push((new_idx, *other_idx));
+            }
+        }
+
+        Some((node, meetings))
+    }
+
+    // The depth of the next path to be developed
+    fn next_depth(&self) -> Option<usize> {
+        self.queue.front().map(|(_, depth)| *depth)
+    }
+}
+
+// The Bidirectional BFS searcher grows two breadth-first frontiers, one from the start node and one from the end node,
+// developing one whole layer (all the paths of the same depth) of one frontier before switching to the smaller frontier.
+// As soon as a frontier reaches a cell already reached by the other one, both paths are stitched into the solution,
+// which is a shortest path since the frontiers only grow one complete layer at a time.
+pub struct BidirectionalBfsSearcher {
+    maze: Rc<Maze>,
+    forward: Frontier,
+    backward: Frontier,
+    side: Side,
+    layer: usize,
+    solution: Option<Path>,
+    done: bool,
+}
+
+impl BidirectionalBfsSearcher {
+    pub fn new(maze: Rc<Maze>) -> BidirectionalBfsSearcher {
+        let forward = Frontier::new(maze.get_start());
+        let mut backward = Frontier::new(maze.get_end());
+
+        // The end node is developed right away, so that the searcher never yields it before the paths have met
+        backward.develop(&forward);
+
+        BidirectionalBfsSearcher {
+            maze,
+            forward,
+            backward,
+            side: Side::Forward,
+            layer: 0,
+            solution: None,
+            done: false,
+        }
+    }
+
+    fn frontier(&self, side: Side) -> &Frontier {
+        match side {
+            Side::Forward => &self.forward,
+            Side::Backward => &self.backward,
+        }
+    }
+
+    // Builds the full path from a path of the forward frontier and a path of the backward frontier ending on the same cell.
+    fn stitch(&self, forward_idx: PathIdx, backward_idx: PathIdx) -> Path {
+        let mut nodes: Vec<MazeNode> = self.forward.arena.ancestors(forward_idx).cloned().collect();
+        nodes.reverse();
+        nodes.extend(self.backward.arena.ancestors(backward_idx).skip(1).cloned());
+        nodes.into_iter().collect()
+    }
+
+    // When the current layer is over, switch to the frontier with the fewest paths left to develop.
+    fn select_side(&mut self) {
+        if self.frontier(self.side).next_depth() == Some(self.layer) {
+            return;
+        }
+
+        self.side = match (self.forward.queue.len(), self.backward.queue.len()) {
+            (0, _) => Side::Backward,
+            (_, 0) => Side::Forward,
+            (forward_len, backward_len) if forward_len <= backward_len => Side::Forward,
+            _ => Side::Backward,
+        };
+        self.layer = self.frontier(self.side).next_depth().unwrap_or_default();
+    }
+}
+
+impl super::Searcher for BidirectionalBfsSearcher {
+    // To get the current path, return the stitched solution if the frontiers have met, or else the path
+    // (from the start or from the end node) to the next node to be developed.
+    fn get_current_path(&self) -> Option<Path> {
+        if self.solution.is_some() {
+            return self.solution.clone();
+        }
+
+        let frontier = self.frontier(self.side);
+        frontier
+            .queue
+            .front()
+            .map(|(idx, _)| frontier.arena.path(*idx))
+    }
+
+    // To get the considered nodes, return the neighbours of the tail of each path, in both frontiers.
+    fn get_considered_nodes(&self) -> Vec<MazeNode> {
+        [&self.forward, &self.backward]
+            .iter()
+            .flat_map(|frontier| {
+                frontier
+                    .queue
+                    .iter()
+                    .filter_map(|(idx, _)| frontier.arena.node(*idx).cloned())
+            })
+            .flat_map(|node| node.get_neighbors())
+            .collect()
+    }
+
+    // To develop the next node, develop the next path of the current layer in the current frontier.
+    // Once the frontiers have met, the end node is yielded to signal that the search is done.
+    fn develop_next_node(&mut self) -> Option<MazeNode> {
+        if self.done {
+            return None;
+        }
+        if self.solution.is_some() {
+            self.done = true;
+            return Some(self.maze.get_end());
+        }
+
+        self.select_side();
+        let (node, meetings) = match self.side {
+            Side::Forward => self.forward.develop(&self.backward)?,
+            Side::Backward => self.backward.develop(&self.forward)?,
+        };
+
+        if let Some((own_idx, other_idx)) = meetings.first() {
+            self.solution = Some(match self.side {
+                Side::Forward => self.stitch(*own_idx, *other_idx),
+                Side::Backward => self.stitch(*other_idx, *own_idx),
+            });
+        }
+
+        Some(node)
+    }
+}
+
+impl Iterator for BidirectionalBfsSearcher {
+    type Item = MazeNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.develop_next_node()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, rc::Rc};
+
+    use super::BidirectionalBfsSearcher;
+    use crate::maze::{Connectivity, CornerCutting, Maze};
+    use crate::search::bfs::BreadthFirstSearcher;
+    use crate::test_utils::{load_example, solve, EXAMPLES};
+    use crate::tilemap::TileMap;
+
+    const CONNECTIVITIES: [Connectivity; 3] = [
+        Connectivity::Four,
+        Connectivity::Eight(CornerCutting::Never),
+        Connectivity::Eight(CornerCutting::Always),
+    ];
+
+    fn assert_matches_bfs(maze: Rc<Maze>, context: &str) -> Result<(), Box<dyn Error>> {
+        let bfs = solve(&mut BreadthFirstSearcher::new(&maze)).ok_or("BFS should find a path")?;
+        let bidirectional = solve(&mut BidirectionalBfsSearcher::new(maze.clone()))
+            .ok_or(format!("{context}: bidirectional BFS should find a path"))?;
+        assert_eq!(bidirectional.first(), Some(&maze.get_start()), "{context}");
+        assert_eq!(bidirectional.last(), Some(&maze.get_end()), "{context}");
+        assert_eq!(bidirectional.len(), bfs.len(), "{context}");
+        assert_eq!(bidirectional.cost(), bfs.cost(), "{context}");
+        Ok(())
+    }
+
+    #[test]
+    fn matches_bfs_on_examples() -> Result<(), Box<dyn Error>> {
+        for example in EXAMPLES {
+            for connectivity in CONNECTIVITIES {
+                let maze = Maze::try_from(load_example(example)?)?.with_connectivity(connectivity);
+                assert_matches_bfs(Rc::new(maze), &format!("{example} {connectivity:?}"))?;
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn matches_bfs_on_odd_and_even_distances() -> Result<(), Box<dyn Error>> {
+        // The frontiers meet on a cell when the distance is even, and across a move when it is odd
+        for text in [
+            "1111\n1231\n1111",
+            "11111\n12031\n11111",
+            "111111\n120031\n111111",
+            "111111\n120001\n100001\n100031\n111111",
+            "1111111\n1200001\n1011101\n1000031\n1111111",
+        ] {
+            let maze = Maze::try_from(TileMap::try_from(String::from(text))?)?;
+            assert_matches_bfs(Rc::new(maze), text)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn gives_up_when_the_end_is_unreachable() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::try_from(TileMap::try_from(String::from(
+            "1111111\n1200131\n1111111",
+        ))?)?;
+        assert!(solve(&mut BidirectionalBfsSearcher::new(Rc::new(maze))).is_none());
+        Ok(())
+    }
+}
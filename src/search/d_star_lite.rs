@@ -0,0 +1,367 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    rc::Rc,
+    sync::mpsc::Receiver,
+};
+
+use crate::{
+    maze::{Coordinates, Cost, Maze, MazeChange, MazeEditor},
+    tilemap::Tile,
+};
+
+use super::{a_star::HeuristicFn, path::Path, MazeNode, Searcher};
+
+// The cost of the cells that cannot reach the end node (yet)
+const INFINITY: Cost = Cost::MAX;
+
+// The priority of a cell: (min(g, rhs) + heuristic + km, min(g, rhs)), lowest first
+type Key = (Cost, Cost);
+
+// The D* Lite searcher searches backwards, from the end node to the start node, and keeps two costs for each cell:
+// g, the cost to the end node as of the last time the cell was developed, and rhs, the cost computed from the
+// neighbours' g. Cells where both differ are "inconsistent" and wait in the queue to be developed.
+// When the maze changes, only the cells around the change get their rhs recomputed, so repairing the solution only
+// develops the cells whose cost actually changed instead of searching from scratch. The start node can also move
+// (as a robot walking along the path does), which is accounted for by the km offset instead of rebuilding the queue.
+pub struct DStarLiteSearcher<F>
+where
+    F: HeuristicFn,
+{
+    maze: Rc<Maze>,
+    heuristic: Box<F>,
+    changes: Option<Receiver<MazeChange>>,
+    start: Coordinates,
+    // The start node the keys in the queue were computed for, and the heuristic distance it moved since then
+    last_start: Coordinates,
+    km: Cost,
+    g: HashMap<Coordinates, Cost>,
+    rhs: HashMap<Coordinates, Cost>,
+    queue: BinaryHeap<(Reverse<(Key, u64)>, Coordinates)>,
+    // The current key of each cell in the queue, so that outdated entries can be skipped
+    queued: HashMap<Coordinates, Key>,
+    insertions: u64,
+    // Whether the end node has been yielded since the solution was last repaired
+    yielded_end: bool,
+}
+
+impl<F: HeuristicFn> DStarLiteSearcher<F> {
+    pub fn new(maze: Rc<Maze>, heuristic: Box<F>) -> DStarLiteSearcher<F> {
+        let start = maze.get_start().get_coordinates();
+        let end = maze.get_end().get_coordinates();
+
+        let mut searcher = DStarLiteSearcher {
+            maze,
+            heuristic,
+            changes: None,
+            start,
+            last_start: start,
+            km: 0,
+            g: HashMap::new(),
+            rhs: [(end, 0)].into(),
+            queue: BinaryHeap::new(),
+            queued: HashMap::new(),
+            insertions: 0,
+            yielded_end: false,
+        };
+        searcher.insert(end);
+
+        // The end node is developed right away, so that the searcher never yields it before the solution is found.
+        // Its cost is always 0, so it never needs to be developed again.
+        searcher.develop_next_node();
+        searcher
+    }
+
+    // Builds a searcher that follows the changes made through the editor, repairing its solution after each one.
+    pub fn watching(editor: &mut MazeEditor, heuristic: Box<F>) -> DStarLiteSearcher<F> {
+        let mut searcher = DStarLiteSearcher::new(editor.maze(), heuristic);
+        searcher.changes = Some(editor.subscribe());
+        searcher
+    }
+
+    fn g(&self, coord: Coordinates) -> Cost {
+        self.g.get(&coord).copied().unwrap_or(INFINITY)
+    }
+
+    fn rhs(&self, coord: Coordinates) -> Cost {
+        self.rhs.get(&coord).copied().unwrap_or(INFINITY)
+    }
+
+    // The heuristic between two cells. The search goes backwards, so cells are estimated against the start node.
+    fn heuristic(&self, from: Coordinates, to: Coordinates) -> Cost {
+        match (self.maze.get_node(from), self.maze.get_node(to)) {
+            (Some(from), Some(to)) => (self.heuristic)(&from, &to),
+            _ => 0,
+        }
+    }
+
+    fn key(&self, coord: Coordinates) -> Key {
+        let cost = self.g(coord).min(self.rhs(coord));
+        (
+            cost.saturating_add(self.heuristic(coord, self.start))
+                .saturating_add(self.km),
+            cost,
+        )
+    }
+
+    fn insert(&mut self, coord: Coordinates) {
+        let key = self.key(coord);
+        self.queued.insert(coord, key);
+        self.queue.push((Reverse((key, self.insertions)), coord));
+        self.insertions += 1;
+    }
+
+    // Drops the outdated entries on top of the queue, so that the top entry is always the next one to be developed.
+    fn discard_outdated(&mut self) {
+        while let Some((Reverse((key, _)), coord)) = self.queue.peek() {
+            if self.queued.get(coord) == Some(key) {
+                break;
+            }
+            self.queue.pop();
+        }
+    }
+
+    // The walkable cells one move away from a cell. Moves are allowed both ways, so these are both the cells that
+    // can be reached from it and the cells that can reach it.
+    fn neighbours(&self, coord: Coordinates) -> Vec<Coordinates> {
+        self.maze.get_node(coord).map_or(Vec::new(), |node| {
+            node.get_neighbors()
+                .into_iter()
+                .map(|neighbour| neighbour.get_coordinates())
+                .collect()
+        })
+    }
+
+    // The cheapest cost to the end node through one of the neighbours of a cell, or infinity for walls
+    fn lookahead(&self, coord: Coordinates) -> Cost {
+        let is_wall = self
+            .maze
+            .get_node(coord)
+            .is_none_or(|node| node.get_tile() == Tile::Wall);
+        if is_wall {
+            return INFINITY;
+        }
+
+        self.neighbours(coord)
+            .into_iter()
+            .filter_map(|neighbour| {
+                let step_cost = self.maze.step_cost(coord, neighbour)?;
+                Some(step_cost.saturating_add(self.g(neighbour)))
+            })
+            .min()
+            .unwrap_or(INFINITY)
+    }
+
+    // Recomputes the rhs of a cell, and puts it in the queue if (and only if) it is inconsistent.
+    fn update_cell(&mut self, coord: Coordinates) {
+        if coord != self.maze.get_end().get_coordinates() {
+            let rhs = self.lookahead(coord);
+            self.rhs.insert(coord, rhs);
+        }
+        self.queued.remove(&coord);
+        if self.g(coord) != self.rhs(coord) {
+            self.insert(coord);
+        }
+    }
+
+    // Whether the costs of the start node are not final yet, so that more cells must be developed
+    fn needs_repair(&self) -> bool {
+        let top_key = self.queue.peek().map(|(Reverse((key, _)), _)| *key);
+        top_key.is_some_and(|key| key < self.key(self.start))
+            || self.rhs(self.start) != self.g(self.start)
+    }
+
+    // Takes the changes made to the maze into account: the changed cell and all the cells around it (whose moves
+    // may pass by it) get their rhs recomputed.
+    pub fn apply_change(&mut self, change: &MazeChange) {
+        self.maze = change.maze.clone();
+
+        let (x, y) = change.coord;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if let Some(coord) = x.checked_add_signed(dx).zip(y.checked_add_signed(dy)) {
+                    if coord.0 < self.maze.width() && coord.1 < self.maze.height() {
+                        self.update_cell(coord);
+                    }
+                }
+            }
+        }
+
+        self.discard_outdated();
+        self.yielded_end = false;
+    }
+
+    // Moves the start node (for instance, as a robot walks along the path). The keys in the queue are kept, and the
+    // heuristic distance moved is added to km so that new keys stay comparable with them.
+    pub fn move_start(&mut self, coord: Coordinates) {
+        self.km = self
+            .km
+            .saturating_add(self.heuristic(self.last_start, coord));
+        self.last_start = coord;
+        self.start = coord;
+        self.yielded_end = false;
+    }
+
+    // Applies the changes notified since the last call, if the searcher follows an editor
+    fn poll_changes(&mut self) {
+        let changes: Vec<MazeChange> = self
+            .changes
+            .as_ref()
+            .map_or(Vec::new(), |changes| changes.try_iter().collect());
+        for change in &changes {
+            self.apply_change(change);
+        }
+    }
+
+    // Follows the cheapest moves from a cell to the end node. The walk stops early if it reaches a cell that
+    // cannot reach the end node, or a cell it has already visited (which can happen while costs are being repaired).
+    fn descend(&self, from: Coordinates) -> Path {
+        let end = self.maze.get_end().get_coordinates();
+        let mut visited = HashSet::new();
+        let mut current = from;
+        let mut path: Path = self.maze.get_node(from).into_iter().collect();
+
+        while current != end && visited.insert(current) {
+            let next = self
+                .neighbours(current)
+                .into_iter()
+                .filter_map(|neighbour| {
+                    let step_cost = self.maze.step_cost(current, neighbour)?;
+                    Some((step_cost.saturating_add(self.g(neighbour)), neighbour))
+                })
+                .filter(|(cost, _)| *cost < INFINITY)
+                .min();
+            let Some(node) = next.and_then(|(_, next)| self.maze.get_node(next)) else {
+                break;
+            };
+            current = node.get_coordinates();
+            path.push(node);
+        }
+        path
+    }
+
+    pub fn start(&self) -> Coordinates {
+        self.start
+    }
+}
+
+impl<F: HeuristicFn> super::Searcher for DStarLiteSearcher<F> {
+    // To get the current path, follow the cheapest moves from the start node once its cost is final, or else from
+    // the next cell to be developed.
+    fn get_current_path(&self) -> Option<Path> {
+        if !self.needs_repair() {
+            return (self.g(self.start) < INFINITY).then(|| self.descend(self.start));
+        }
+        self.queue.peek().map(|(_, coord)| self.descend(*coord))
+    }
+
+    // To get the considered nodes, return the cells waiting in the queue.
+    fn get_considered_nodes(&self) -> Vec<MazeNode> {
+        self.queued
+            .keys()
+            .filter_map(|coord| self.maze.get_node(*coord))
+            .collect()
+    }
+
+    // To develop the next node, take the cell with the lowest key. If its key is outdated it is put back with the new
+    // key; otherwise its g is set to its rhs (or to infinity if its cost went up, so that it is developed again), and
+    // its neighbours get their rhs recomputed. Once the cost of the start node is final, the end node is yielded
+    // to signal that the search is done, until the maze changes or the start node moves.
+    fn develop_next_node(&mut self) -> Option<MazeNode> {
+        self.poll_changes();
+
+        if !self.needs_repair() {
+            if self.yielded_end || self.g(self.start) == INFINITY {
+                return None;
+            }
+            self.yielded_end = true;
+            return Some(self.maze.get_end());
+        }
+
+        let (Reverse((old_key, _)), coord) = self.queue.pop()?;
+        self.queued.remove(&coord);
+        let node = self.maze.get_node(coord)?;
+
+        let new_key = self.key(coord);
+        if old_key < new_key {
+            self.insert(coord);
+        } else if self.g(coord) > self.rhs(coord) {
+            self.g.insert(coord, self.rhs(coord));
+            for neighbour in self.neighbours(coord) {
+                self.update_cell(neighbour);
+            }
+        } else {
+            self.g.insert(coord, INFINITY);
+            self.update_cell(coord);
+            for neighbour in self.neighbours(coord) {
+                self.update_cell(neighbour);
+            }
+        }
+
+        self.discard_outdated();
+        Some(node)
+    }
+}
+
+impl<F: HeuristicFn> Iterator for DStarLiteSearcher<F> {
+    type Item = MazeNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.develop_next_node()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, rc::Rc};
+
+    use super::DStarLiteSearcher;
+    use crate::generate::{Rng, SplitMix64};
+    use crate::maze::{Connectivity, CornerCutting, Cost, Maze, MazeEditor};
+    use crate::search::{dijkstra::DijkstraSearcher, heuristics, Searcher};
+    use crate::test_utils::{load_example, solve};
+
+    // Runs the searcher until its solution is repaired, and returns the cost of the path it found
+    fn repaired_cost(searcher: &mut dyn Searcher) -> Option<Cost> {
+        while searcher.next().is_some() {}
+        searcher.get_current_path().and_then(|path| path.cost())
+    }
+
+    #[test]
+    fn repairs_match_dijkstra_after_toggling_walls() -> Result<(), Box<dyn Error>> {
+        for connectivity in [
+            Connectivity::Four,
+            Connectivity::Eight(CornerCutting::Never),
+        ] {
+            let maze = Maze::try_from(load_example("10x10")?)?.with_connectivity(connectivity);
+            let mut editor = MazeEditor::new(Rc::new(maze));
+            let mut searcher =
+                DStarLiteSearcher::watching(&mut editor, Box::new(heuristics::octile));
+            let mut rng = SplitMix64::new(17);
+            let mut last_toggled = None;
+
+            for toggle in 0..300 {
+                let maze = editor.maze();
+                let optimal =
+                    solve(&mut DijkstraSearcher::new(maze.clone())).and_then(|path| path.cost());
+                assert_eq!(
+                    repaired_cost(&mut searcher),
+                    optimal,
+                    "after {toggle} toggles"
+                );
+
+                // Toggle a random cell, or the last one again if it cut the start node from the end node, so that
+                // most repairs still find a path. Toggling the start or end tiles fails, and leaves the maze as it is.
+                let coord = match (optimal, last_toggled) {
+                    (None, Some(coord)) => coord,
+                    _ => (
+                        1 + rng.below(maze.width() - 2),
+                        1 + rng.below(maze.height() - 2),
+                    ),
+                };
+                last_toggled = editor.toggle_wall(coord).ok().map(|_| coord);
+            }
+        }
+        Ok(())
+    }
+}
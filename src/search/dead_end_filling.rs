@@ -0,0 +1,386 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    rc::Rc,
+};
+
+use crate::{
+    maze::{Coordinates, Maze},
+    tilemap::{Tile, TileMap},
+};
+
+use super::{dijkstra::DijkstraSearcher, path::Path, MazeNode, Searcher};
+
+// The Dead-End Filler solves the maze by filling it in rather than by searching through it. It repeatedly fills the
+// dead ends (cells other than the start and end nodes with at most one open neighbour), which turns the cell leading
+// to each of them into a new dead end, until only the corridors between the start and end nodes remain.
+// Dead-end filling leaves the loops of the maze open, so once no dead end is left, the cul-de-sacs are pruned as well:
+// the parts of the maze hanging off a single cell (or not reachable at all) that do not hold the start or end node,
+// as no path can go into them and come back out without going through that cell twice.
+// Each developed node is a filled cell. Once everything is filled, the cells left are exactly those lying on some
+// direct path between the start and end nodes, and the end node is yielded with the cheapest of these paths.
+pub struct DeadEndFiller {
+    maze: Rc<Maze>,
+    filled: HashSet<Coordinates>,
+    // The dead ends waiting to be filled
+    dead_ends: VecDeque<Coordinates>,
+    // The cells of the cul-de-sacs waiting to be filled, once no dead end is left
+    cul_de_sacs: VecDeque<Coordinates>,
+    // Every cell that has been queued, so that no cell is queued twice
+    queued: HashSet<Coordinates>,
+    cul_de_sacs_found: bool,
+    solution: Option<Path>,
+    done: bool,
+}
+
+impl DeadEndFiller {
+    pub fn new(maze: Rc<Maze>) -> DeadEndFiller {
+        let mut filler = DeadEndFiller {
+            maze,
+            filled: HashSet::new(),
+            dead_ends: VecDeque::new(),
+            cul_de_sacs: VecDeque::new(),
+            queued: HashSet::new(),
+            cul_de_sacs_found: false,
+            solution: None,
+            done: false,
+        };
+
+        let dead_ends: Vec<Coordinates> = (0..filler.maze.height())
+            .flat_map(|y| (0..filler.maze.width()).map(move |x| (x, y)))
+            .filter(|coord| filler.is_dead_end(*coord))
+            .collect();
+        filler.queued.extend(dead_ends.iter().copied());
+        filler.dead_ends.extend(dead_ends);
+        filler.prepare_next_fill();
+        filler
+    }
+
+    // Whether a cell can be filled: a walkable cell other than the start and end nodes, not filled yet
+    fn is_fillable(&self, coord: Coordinates) -> bool {
+        !self.filled.contains(&coord)
+            && self
+                .maze
+                .get_node(coord)
+                .is_some_and(|node| node.get_tile().weight().is_some())
+            && coord != self.maze.get_start().get_coordinates()
+            && coord != self.maze.get_end().get_coordinates()
+    }
+
+    fn is_dead_end(&self, coord: Coordinates) -> bool {
+        self.is_fillable(coord) && self.open_neighbours(coord).len() <= 1
+    }
+
+    // The neighbours of a cell that are not filled
+    fn open_neighbours(&self, coord: Coordinates) -> Vec<Coordinates> {
+        self.maze.get_node(coord).map_or(Vec::new(), |node| {
+            node.get_neighbors()
+                .into_iter()
+                .map(|neighbour| neighbour.get_coordinates())
+                .filter(|neighbour| !self.filled.contains(neighbour))
+                .collect()
+        })
+    }
+
+    // The next cell to be filled: the dead ends first, then the cul-de-sacs
+    fn next_fill(&self) -> Option<Coordinates> {
+        self.dead_ends.front().or(self.cul_de_sacs.front()).copied()
+    }
+
+    // Fills a cell, and queues the neighbours it turned into dead ends.
+    fn fill(&mut self, coord: Coordinates) {
+        self.filled.insert(coord);
+        for neighbour in self.open_neighbours(coord) {
+            if !self.queued.contains(&neighbour) && self.is_dead_end(neighbour) {
+                self.queued.insert(neighbour);
+                self.dead_ends.push_back(neighbour);
+            }
+        }
+    }
+
+    // Once no dead end is left, looks for the cul-de-sacs, and once those are filled too, finds the solution.
+    fn prepare_next_fill(&mut self) {
+        if self.next_fill().is_some() {
+            return;
+        }
+        if !self.cul_de_sacs_found {
+            self.cul_de_sacs_found = true;
+            let cul_de_sacs: Vec<Coordinates> = self
+                .find_cul_de_sacs()
+                .into_iter()
+                .filter(|coord| self.queued.insert(*coord))
+                .collect();
+            self.cul_de_sacs.extend(cul_de_sacs);
+            if self.next_fill().is_some() {
+                return;
+            }
+        }
+        self.solution = self.find_solution();
+    }
+
+    // Finds the open cells that no direct path between the start and end nodes goes through, using the articulation
+    // points of a depth-first search from the start node (as in Tarjan's algorithm). When the subtree below a cell
+    // has no way back above it, that cell is the only way into the subtree, which is a cul-de-sac unless it holds the
+    // end node. The cells the search never reaches are cul-de-sacs as well.
+    fn find_cul_de_sacs(&self) -> Vec<Coordinates> {
+        let start = self.maze.get_start().get_coordinates();
+        let end = self.maze.get_end().get_coordinates();
+
+        // The cells in the order they were discovered, and for each one (by discovery index) the lowest discovery
+        // index reachable from its subtree through a single back edge, and the size of its subtree
+        let mut discovered: HashMap<Coordinates, usize> = [(start, 0)].into();
+        let mut order = vec![start];
+        let mut low = vec![0];
+        let mut sizes = vec![1];
+        // The subtrees that can only be entered through their parent
+        let mut separated = Vec::new();
+
+        let mut stack = vec![(start, self.open_neighbours(start))];
+        while let Some((coord, neighbours)) = stack.last_mut() {
+            let index = discovered.get(coord).copied().unwrap_or_default();
+            if let Some(next) = neighbours.pop() {
+                if let Some(next_index) = discovered.get(&next) {
+                    low[index] = low[index].min(*next_index);
+                } else {
+                    let next_index = order.len();
+                    discovered.insert(next, next_index);
+                    order.push(next);
+                    low.push(next_index);
+                    sizes.push(1);
+                    stack.push((next, self.open_neighbours(next)));
+                }
+                continue;
+            }
+
+            stack.pop();
+            if let Some((parent, _)) = stack.last() {
+                let parent_index = discovered.get(parent).copied().unwrap_or_default();
+                low[parent_index] = low[parent_index].min(low[index]);
+                sizes[parent_index] += sizes[index];
+                if low[index] >= parent_index {
+                    separated.push(index);
+                }
+            }
+        }
+
+        // Mark the separated subtrees that do not hold the end node, as differences over the discovery order
+        let end_index = discovered.get(&end).copied();
+        let mut marks = vec![0isize; order.len() + 1];
+        for index in separated {
+            let subtree = index..index + sizes[index];
+            if !end_index.is_some_and(|end_index| subtree.contains(&end_index)) {
+                marks[subtree.start] += 1;
+                marks[subtree.end] -= 1;
+            }
+        }
+
+        let mut depth = 0;
+        let mut cul_de_sacs: Vec<Coordinates> = order
+            .iter()
+            .zip(marks)
+            .filter_map(|(coord, mark)| {
+                depth += mark;
+                (depth > 0).then_some(*coord)
+            })
+            .collect();
+        cul_de_sacs.extend(
+            (0..self.maze.height())
+                .flat_map(|y| (0..self.maze.width()).map(move |x| (x, y)))
+                .filter(|coord| !discovered.contains_key(coord) && self.is_fillable(*coord)),
+        );
+        cul_de_sacs
+    }
+
+    // The cheapest path through the cells left, if the start and end nodes are still connected. The path is searched
+    // on the pruned maze, and its nodes are then taken from the original maze, so that they compare equal to the
+    // nodes of the maze being solved.
+    fn find_solution(&self) -> Option<Path> {
+        let maze = Rc::new(
+            Maze::try_from(self.pruned_tilemap())
+                .ok()?
+                .with_connectivity(self.maze.connectivity()),
+        );
+        let mut searcher = DijkstraSearcher::new(maze.clone());
+        loop {
+            let path = searcher.get_current_path()?;
+            if searcher.next()? == maze.get_end() {
+                return Some(
+                    path.iter()
+                        .filter_map(|node| self.maze.get_node(node.get_coordinates()))
+                        .collect(),
+                );
+            }
+        }
+    }
+
+    // The maze with every cell filled so far turned into a wall
+    pub fn pruned_tilemap(&self) -> TileMap {
+        let mut tilemap = self.maze.tilemap().clone();
+        for (x, y) in &self.filled {
+            tilemap.set(*x, *y, Tile::Wall);
+        }
+        tilemap
+    }
+
+    // The number of cells filled so far
+    pub fn filled(&self) -> usize {
+        self.filled.len()
+    }
+}
+
+// Fills every dead end and cul-de-sac of the maze at once, and returns the map of the corridors left.
+pub fn prune(maze: Rc<Maze>) -> TileMap {
+    let mut filler = DeadEndFiller::new(maze);
+    while filler.next_fill().is_some() {
+        filler.develop_next_node();
+    }
+    filler.pruned_tilemap()
+}
+
+impl super::Searcher for DeadEndFiller {
+    // To get the current path, return the next cell to be filled, or the solution once everything is filled.
+    fn get_current_path(&self) -> Option<Path> {
+        match self.next_fill() {
+            Some(coord) => self
+                .maze
+                .get_node(coord)
+                .map(|node| [node].into_iter().collect()),
+            None => self.solution.clone(),
+        }
+    }
+
+    // To get the considered nodes, return the cells waiting to be filled.
+    fn get_considered_nodes(&self) -> Vec<MazeNode> {
+        self.dead_ends
+            .iter()
+            .chain(&self.cul_de_sacs)
+            .filter_map(|coord| self.maze.get_node(*coord))
+            .collect()
+    }
+
+    // To develop the next node, fill the next dead end (or cell of a cul-de-sac). Once everything is filled, the end
+    // node is yielded to signal that the search is done.
+    fn develop_next_node(&mut self) -> Option<MazeNode> {
+        if self.done {
+            return None;
+        }
+        let Some(coord) = self
+            .dead_ends
+            .pop_front()
+            .or_else(|| self.cul_de_sacs.pop_front())
+        else {
+            self.done = true;
+            return self.solution.is_some().then(|| self.maze.get_end());
+        };
+
+        self.fill(coord);
+        self.prepare_next_fill();
+        self.maze.get_node(coord)
+    }
+
+    fn fills_developed_nodes(&self) -> bool {
+        true
+    }
+}
+
+impl Iterator for DeadEndFiller {
+    type Item = MazeNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.develop_next_node()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, error::Error, rc::Rc};
+
+    use super::{prune, DeadEndFiller};
+    use crate::generate::{braid, generate, Algorithm, Placement, SplitMix64};
+    use crate::maze::{Cost, Maze};
+    use crate::search::{bfs::BreadthFirstSearcher, dijkstra::DijkstraSearcher};
+    use crate::test_utils::{load_example, solve, EXAMPLES};
+    use crate::tilemap::{Tile, TileMap};
+
+    fn dijkstra_cost(maze: &Rc<Maze>) -> Option<Cost> {
+        solve(&mut DijkstraSearcher::new(maze.clone())).and_then(|path| path.cost())
+    }
+
+    fn assert_matches_dijkstra(tilemap: TileMap, context: &str) -> Result<(), Box<dyn Error>> {
+        let maze = Rc::new(Maze::try_from(tilemap)?);
+        let dijkstra = dijkstra_cost(&maze).ok_or("Dijkstra should find a path")?;
+        let filled = solve(&mut DeadEndFiller::new(maze.clone()))
+            .ok_or(format!("{context}: dead-end filling should find a path"))?;
+        assert_eq!(filled.first(), Some(&maze.get_start()), "{context}");
+        assert_eq!(filled.last(), Some(&maze.get_end()), "{context}");
+        assert_eq!(filled.cost(), Some(dijkstra), "{context}");
+
+        // Pruning never cuts off the cheapest path
+        let pruned = Rc::new(Maze::try_from(prune(maze))?);
+        assert_eq!(dijkstra_cost(&pruned), Some(dijkstra), "{context}");
+        Ok(())
+    }
+
+    #[test]
+    fn matches_dijkstra_on_examples() -> Result<(), Box<dyn Error>> {
+        for example in EXAMPLES {
+            assert_matches_dijkstra(load_example(example)?, example)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn matches_dijkstra_on_braided_mazes_with_mud() -> Result<(), Box<dyn Error>> {
+        for seed in 0..5 {
+            let mut rng = SplitMix64::new(seed);
+            let mut tilemap = braid(
+                &generate(Algorithm::Prim, 31, 31, Placement::Farthest, seed)?,
+                0.5,
+                &mut rng,
+            )?;
+            // Flood some corridors, so that the cheapest path is not always the shortest one
+            for y in 0..tilemap.height() {
+                for x in 0..tilemap.width() {
+                    if tilemap.get(x, y) == Some(Tile::Empty) && x % 4 == 1 && y % 3 == 0 {
+                        tilemap.set(x, y, Tile::Mud);
+                    }
+                }
+            }
+            assert_matches_dijkstra(tilemap, &format!("seed {seed}"))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn only_the_solution_is_left_in_perfect_mazes() -> Result<(), Box<dyn Error>> {
+        for seed in 0..5 {
+            let tilemap = generate(
+                Algorithm::RecursiveBacktracker,
+                31,
+                21,
+                Placement::Border,
+                seed,
+            )?;
+            let maze = Rc::new(Maze::try_from(tilemap)?);
+            let bfs =
+                solve(&mut BreadthFirstSearcher::new(&maze)).ok_or("BFS should find a path")?;
+            let solution: HashSet<_> = bfs.iter().map(|node| node.get_coordinates()).collect();
+
+            let pruned = prune(maze);
+            let open: HashSet<_> = (0..pruned.height())
+                .flat_map(|y| (0..pruned.width()).map(move |x| (x, y)))
+                .filter(|(x, y)| pruned.get(*x, *y) != Some(Tile::Wall))
+                .collect();
+            assert_eq!(open, solution, "seed {seed}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn gives_up_when_the_end_is_unreachable() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::try_from(TileMap::try_from(String::from(
+            "1111111\n1200131\n1111111",
+        ))?)?;
+        assert!(solve(&mut DeadEndFiller::new(Rc::new(maze))).is_none());
+        Ok(())
+    }
+}
@@ -2,39 +2,48 @@ use std::rc::Rc;
 
 use crate::maze::Maze;
 
-use super::{path::Path, Searcher};
+use super::{
+    path::{Path, PathArena, PathIdx},
+    Searcher,
+};
 
-// The Depth-First Searcher object stores a vector of all the paths that are being considered.
-pub struct DepthFirstSearcher(Vec<Path>);
+// The Depth-First Searcher object stores an arena of all the paths developed so far, and a stack with the indices of the paths that are being considered.
+pub struct DepthFirstSearcher {
+    arena: PathArena,
+    stack: Vec<PathIdx>,
+}
 
 impl DepthFirstSearcher {
     pub fn new(maze: &Rc<Maze>) -> DepthFirstSearcher {
-        let mut initial_path = Path::new();
-        initial_path.push(maze.get_start());
-        DepthFirstSearcher(vec![initial_path])
+        let mut arena = PathArena::new();
+        let initial_path = arena.push(maze.get_start(), None);
+        DepthFirstSearcher {
+            arena,
+            stack: vec![initial_path],
+        }
     }
 }
 
 impl super::Searcher for DepthFirstSearcher {
-    // To get the current path, return the last path in the vector.
-    fn get_current_path(&self) -> Option<&Path> {
-        self.0.last()
+    // To get the current path, return the last path in the stack.
+    fn get_current_path(&self) -> Option<Path> {
+        self.stack.last().map(|idx| self.arena.path(*idx))
     }
 
     // To get the considered nodes, return the neighbours of the tail of each path.
     fn get_considered_nodes(&self) -> Vec<crate::maze::MazeNode> {
-        self.0
+        self.stack
             .iter()
-            .filter_map(|path| path.last().cloned())
+            .filter_map(|idx| self.arena.node(*idx).cloned())
             .flat_map(|node| node.get_neighbors())
             .collect()
     }
 
-    // To develop the next node, pop the last path from the vector and deepen it.
+    // To develop the next node, pop the last path from the stack and deepen it.
     fn develop_next_node(&mut self) -> Option<crate::maze::MazeNode> {
-        let path = self.0.pop()?.to_owned();
-        let node = path.last()?.clone();
-        self.0.append(&mut path.deepen_path());
+        let idx = self.stack.pop()?;
+        let node = self.arena.node(idx)?.clone();
+        self.stack.append(&mut self.arena.deepen(idx));
         Some(node)
     }
 }
@@ -46,3 +55,37 @@ impl Iterator for DepthFirstSearcher {
         self.develop_next_node()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, error::Error, rc::Rc};
+
+    use super::DepthFirstSearcher;
+    use crate::maze::Maze;
+    use crate::search::bfs::BreadthFirstSearcher;
+    use crate::test_utils::{load_example, solve, EXAMPLES};
+
+    #[test]
+    fn finds_simple_paths_no_shorter_than_bfs_on_examples() -> Result<(), Box<dyn Error>> {
+        for example in EXAMPLES {
+            let maze = Rc::new(Maze::try_from(load_example(example)?)?);
+            let bfs = solve(&mut BreadthFirstSearcher::new(&maze))
+                .and_then(|path| path.cost())
+                .ok_or("BFS should find a path")?;
+            let dfs = solve(&mut DepthFirstSearcher::new(&maze))
+                .ok_or(format!("{example}: DFS should find a path"))?;
+
+            assert_eq!(dfs.first(), Some(&maze.get_start()), "{example}");
+            assert_eq!(dfs.last(), Some(&maze.get_end()), "{example}");
+            let distinct: HashSet<_> = dfs.iter().map(|node| node.get_coordinates()).collect();
+            assert_eq!(
+                distinct.len(),
+                dfs.len(),
+                "{example}: the path crosses itself"
+            );
+            let cost = dfs.cost().ok_or(format!("{example}: the path has gaps"))?;
+            assert!(cost >= bfs, "{example}: {cost} < {bfs}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use std::rc::Rc;
+
+use crate::maze::Maze;
+
+use super::{
+    a_star::AStarSearcher,
+    heuristics::{self, Heuristic},
+    path::Path,
+    MazeNode, SearchMode, Searcher,
+};
+
+// Dijkstra's algorithm is A* without any heuristic, so paths are developed strictly by lowest cost.
+// The Dijkstra searcher wraps an A* searcher whose heuristic is always zero.
+pub struct DijkstraSearcher(AStarSearcher<Heuristic>);
+
+impl DijkstraSearcher {
+    pub fn new(maze: Rc<Maze>) -> DijkstraSearcher {
+        DijkstraSearcher::with_mode(maze, SearchMode::default())
+    }
+
+    pub fn with_mode(maze: Rc<Maze>, mode: SearchMode) -> DijkstraSearcher {
+        DijkstraSearcher(AStarSearcher::with_mode(
+            maze,
+            Box::new(heuristics::zero),
+            mode,
+        ))
+    }
+}
+
+impl super::Searcher for DijkstraSearcher {
+    fn get_current_path(&self) -> Option<Path> {
+        self.0.get_current_path()
+    }
+
+    fn get_considered_nodes(&self) -> Vec<MazeNode> {
+        self.0.get_considered_nodes()
+    }
+
+    fn develop_next_node(&mut self) -> Option<MazeNode> {
+        self.0.develop_next_node()
+    }
+}
+
+impl Iterator for DijkstraSearcher {
+    type Item = MazeNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.develop_next_node()
+    }
+}
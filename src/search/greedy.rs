@@ -0,0 +1,119 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+    rc::Rc,
+};
+
+use crate::maze::{Coordinates, Cost, Maze};
+
+use super::{
+    a_star::HeuristicFn,
+    path::{Path, PathArena, PathIdx},
+    MazeNode, SearchMode, Searcher,
+};
+
+// The Greedy Best-First searcher will store an arena of all the paths developed so far, and a priority queue of path
+// indices ordered only by the heuristic of their last node (then by insertion order), ignoring the cost of the path.
+// In graph mode, it also stores every cell that has already been reached, so that no cell is enqueued twice.
+pub struct GreedyBestFirstSearcher<F>
+where
+    F: HeuristicFn,
+{
+    maze: Rc<Maze>,
+    arena: PathArena,
+    frontier: BinaryHeap<(Reverse<(Cost, u64)>, PathIdx)>,
+    heuristic: Box<F>,
+    mode: SearchMode,
+    visited: HashSet<Coordinates>,
+    insertions: u64,
+}
+
+impl<F: HeuristicFn> GreedyBestFirstSearcher<F> {
+    pub fn new(maze: Rc<Maze>, heuristic: Box<F>) -> GreedyBestFirstSearcher<F> {
+        GreedyBestFirstSearcher::with_mode(maze, heuristic, SearchMode::default())
+    }
+
+    pub fn with_mode(
+        maze: Rc<Maze>,
+        heuristic: Box<F>,
+        mode: SearchMode,
+    ) -> GreedyBestFirstSearcher<F> {
+        let start_node = maze.get_start();
+        let visited = [start_node.get_coordinates()].into();
+
+        let mut arena = PathArena::new();
+        let initial_path = arena.push(start_node, None);
+
+        let mut searcher = GreedyBestFirstSearcher {
+            maze,
+            arena,
+            frontier: BinaryHeap::new(),
+            heuristic,
+            mode,
+            visited,
+            insertions: 0,
+        };
+        searcher.push(initial_path);
+        searcher
+    }
+
+    // Adds a path to the frontier, computing the heuristic of its last node.
+    fn push(&mut self, path: PathIdx) {
+        let Some(last) = self.arena.node(path) else {
+            return;
+        };
+        let heuristic = (self.heuristic)(last, &self.maze.get_end());
+
+        self.frontier
+            .push((Reverse((heuristic, self.insertions)), path));
+        self.insertions += 1;
+    }
+}
+
+impl<F: HeuristicFn> super::Searcher for GreedyBestFirstSearcher<F> {
+    // To get the current path, return the path whose last node has the lowest heuristic.
+    fn get_current_path(&self) -> Option<Path> {
+        self.frontier.peek().map(|(_, path)| self.arena.path(*path))
+    }
+
+    // To get the considered nodes, return the neighbours of the last node of each path.
+    fn get_considered_nodes(&self) -> Vec<MazeNode> {
+        self.frontier
+            .iter()
+            .filter_map(|(_, path)| self.arena.node(*path).cloned())
+            .flat_map(|node| node.get_neighbors())
+            .collect()
+    }
+
+    // To develop the next node, take the path with the lowest heuristic and deepen it.
+    // In graph mode, only the neighbours that were never reached before are added to the frontier.
+    fn develop_next_node(&mut self) -> Option<MazeNode> {
+        let (_, path) = self.frontier.pop()?;
+        let node = self.arena.node(path)?.clone();
+
+        match self.mode {
+            SearchMode::Graph => {
+                for neighbour in node.get_neighbors() {
+                    if self.visited.insert(neighbour.get_coordinates()) {
+                        let new_path = self.arena.push(neighbour, Some(path));
+                        self.push(new_path);
+                    }
+                }
+            }
+            SearchMode::Tree => {
+                for new_path in self.arena.deepen(path) {
+                    self.push(new_path);
+                }
+            }
+        }
+        Some(node)
+    }
+}
+
+impl<F: HeuristicFn> Iterator for GreedyBestFirstSearcher<F> {
+    type Item = MazeNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.develop_next_node()
+    }
+}
@@ -0,0 +1,236 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt::{Display, Formatter},
+    rc::Rc,
+};
+
+use crate::{
+    maze::{Coordinates, Cost, Maze},
+    tilemap::Tile,
+};
+
+use super::a_star::HeuristicFn;
+
+// A cell where the heuristic predicts a higher cost than the cheapest path to the end node, which may make
+// A* return a suboptimal path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overestimate {
+    pub coord: Coordinates,
+    pub estimate: Cost,
+    pub true_cost: Cost,
+}
+
+// A move after which the heuristic drops by more than the cost of the move, which may make A* develop a cell
+// before the cheapest path to it is known: h(from) > step_cost + h(to)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inconsistency {
+    pub from: Coordinates,
+    pub to: Coordinates,
+    pub step_cost: Cost,
+    pub estimate_from: Cost,
+    pub estimate_to: Cost,
+}
+
+// Every violation found by check, in row-major order of the cells
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeuristicReport {
+    pub overestimates: Vec<Overestimate>,
+    pub inconsistencies: Vec<Inconsistency>,
+}
+
+impl HeuristicReport {
+    // An admissible heuristic never overestimates, so A* always finds an optimal path
+    pub fn is_admissible(&self) -> bool {
+        self.overestimates.is_empty()
+    }
+
+    // A consistent heuristic is also admissible (as long as it is 0 on the end node), and lets A* in graph mode
+    // develop each cell at most once
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies.is_empty()
+    }
+}
+
+// Checks a heuristic against the whole maze: the heuristic of every cell that can reach the end node is compared to
+// the cost of the cheapest path to it, and the heuristic of every cell is compared across every move out of it.
+pub fn check<F: HeuristicFn>(maze: &Rc<Maze>, heuristic: &F) -> HeuristicReport {
+    let end_node = maze.get_end();
+    let true_costs = costs_to_end(maze);
+    let mut report = HeuristicReport::default();
+
+    for y in 0..maze.height() {
+        for x in 0..maze.width() {
+            let Some(node) = maze
+                .get_node((x, y))
+                .filter(|node| node.get_tile() != Tile::Wall)
+            else {
+                continue;
+            };
+            let estimate = heuristic(&node, &end_node);
+
+            if let Some(true_cost) = true_costs.get(&(x, y)).filter(|cost| estimate > **cost) {
+                report.overestimates.push(Overestimate {
+                    coord: (x, y),
+                    estimate,
+                    true_cost: *true_cost,
+                });
+            }
+
+            for neighbour in node.get_neighbors() {
+                let Some(step_cost) = node.step_cost(&neighbour) else {
+                    continue;
+                };
+                let estimate_to = heuristic(&neighbour, &end_node);
+                if estimate > step_cost.saturating_add(estimate_to) {
+                    report.inconsistencies.push(Inconsistency {
+                        from: (x, y),
+                        to: neighbour.get_coordinates(),
+                        step_cost,
+                        estimate_from: estimate,
+                        estimate_to,
+                    });
+                }
+            }
+        }
+    }
+
+    report
+}
+
+// The cost of the cheapest path from every cell to the end node, by running Dijkstra's algorithm backwards from the
+// end node. Moves are not symmetric on weighted terrain (stepping onto mud costs more than stepping off it), so each
+// cell is relaxed with the cost of the move from its neighbour into it.
+fn costs_to_end(maze: &Rc<Maze>) -> HashMap<Coordinates, Cost> {
+    let end_node = maze.get_end();
+    let mut costs: HashMap<Coordinates, Cost> = [(end_node.get_coordinates(), 0)].into();
+    let mut frontier: BinaryHeap<(Reverse<Cost>, Coordinates)> =
+        [(Reverse(0), end_node.get_coordinates())].into();
+
+    while let Some((Reverse(cost), coord)) = frontier.pop() {
+        if costs.get(&coord).is_some_and(|best| *best < cost) {
+            continue;
+        }
+        let Some(node) = maze.get_node(coord) else {
+            continue;
+        };
+        for neighbour in node.get_neighbors() {
+            let Some(step_cost) = neighbour.step_cost(&node) else {
+                continue;
+            };
+            let new_cost = cost + step_cost;
+            let neighbour_coord = neighbour.get_coordinates();
+            if costs
+                .get(&neighbour_coord)
+                .is_some_and(|best| *best <= new_cost)
+            {
+                continue;
+            }
+            costs.insert(neighbour_coord, new_cost);
+            frontier.push((Reverse(new_cost), neighbour_coord));
+        }
+    }
+
+    costs
+}
+
+impl Display for Overestimate {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "Cell {:?} is estimated at {}, but the end node can be reached for {}",
+            self.coord, self.estimate, self.true_cost
+        )
+    }
+}
+
+impl Display for Inconsistency {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "Moving from {:?} (estimated at {}) to {:?} (estimated at {}) costs only {}",
+            self.from, self.estimate_from, self.to, self.estimate_to, self.step_cost
+        )
+    }
+}
+
+impl Display for HeuristicReport {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "{} overestimated cells ({}), {} inconsistent moves ({})",
+            self.overestimates.len(),
+            if self.is_admissible() {
+                "admissible"
+            } else {
+                "not admissible"
+            },
+            self.inconsistencies.len(),
+            if self.is_consistent() {
+                "consistent"
+            } else {
+                "not consistent"
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, rc::Rc};
+
+    use super::check;
+    use crate::maze::{Connectivity, CornerCutting, Maze};
+    use crate::search::heuristics;
+    use crate::test_utils::{load_example, EXAMPLES};
+    use crate::tilemap::TileMap;
+
+    fn load_maze(example: &str, connectivity: Connectivity) -> Result<Rc<Maze>, Box<dyn Error>> {
+        Ok(Rc::new(
+            Maze::try_from(load_example(example)?)?.with_connectivity(connectivity),
+        ))
+    }
+
+    #[test]
+    fn manhattan_is_admissible_and_consistent_on_four_connected_examples(
+    ) -> Result<(), Box<dyn Error>> {
+        for example in EXAMPLES {
+            let report = check(
+                &load_maze(example, Connectivity::Four)?,
+                &heuristics::manhattan,
+            );
+            assert!(report.is_admissible(), "{example}: {report}");
+            assert!(report.is_consistent(), "{example}: {report}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn manhattan_overestimates_with_diagonal_moves() -> Result<(), Box<dyn Error>> {
+        // The corridors of the bundled mazes only leave room for diagonal moves that cut corners
+        for example in EXAMPLES {
+            let maze = load_maze(example, Connectivity::Eight(CornerCutting::Always))?;
+            assert!(
+                !check(&maze, &heuristics::manhattan).is_admissible(),
+                "{example}"
+            );
+        }
+
+        let room = TileMap::try_from(String::from("11111\n12001\n10001\n10031\n11111"))?;
+        let maze = Rc::new(
+            Maze::try_from(room)?.with_connectivity(Connectivity::Eight(CornerCutting::Never)),
+        );
+        assert!(!check(&maze, &heuristics::manhattan).is_admissible());
+        Ok(())
+    }
+
+    #[test]
+    fn weighted_manhattan_overestimates() -> Result<(), Box<dyn Error>> {
+        for example in EXAMPLES {
+            let maze = load_maze(example, Connectivity::Four)?;
+            let report = check(&maze, &heuristics::weighted(heuristics::manhattan, 2.0));
+            assert!(!report.is_admissible(), "{example}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use crate::maze::{Cost, Maze, STRAIGHT_STEP_COST};
+
+use super::{a_star::HeuristicFn, MazeNode};
+
+// Named heuristics are plain functions, so they can be copied around freely and passed to any informed searcher.
+// All of them are expressed in the same fixed-point unit as step costs (STRAIGHT_STEP_COST per straight step).
+pub type Heuristic = fn(&MazeNode, &MazeNode) -> Cost;
+
+// Heuristics that carry some state, such as precomputed tables, are boxed closures instead
+pub type BoxedHeuristic = Box<dyn Fn(&MazeNode, &MazeNode) -> Cost>;
+
+// The names accepted by from_name, in the order they are listed to the user
+pub const NAMES: [&str; 5] = ["manhattan", "euclidean", "chebyshev", "octile", "zero"];
+
+// The horizontal and vertical distances between two nodes, in cells
+fn deltas(node: &MazeNode, end_node: &MazeNode) -> (Cost, Cost) {
+    let (from, to) = (node.get_coordinates(), end_node.get_coordinates());
+    (
+        usize::abs_diff(from.0, to.0) as Cost,
+        usize::abs_diff(from.1, to.1) as Cost,
+    )
+}
+
+// The number of straight steps between two nodes, ignoring walls.
+// Admissible on 4-connected mazes, but overestimates as soon as diagonal moves are allowed.
+pub fn manhattan(node: &MazeNode, end_node: &MazeNode) -> Cost {
+    Maze::manhattan_distance(node.get_coordinates(), end_node.get_coordinates()) as Cost
+        * STRAIGHT_STEP_COST
+}
+
+// The straight-line distance between two nodes, rounded down.
+// Admissible on any connectivity, but less informed than the grid distances. It is capped by the octile distance,
+// as the diagonal step cost is rounded down below √2 and long diagonals would otherwise be overestimated.
+pub fn euclidean(node: &MazeNode, end_node: &MazeNode) -> Cost {
+    let (dx, dy) = deltas(node, end_node);
+    let distance = ((dx * dx + dy * dy) as f64).sqrt() * STRAIGHT_STEP_COST as f64;
+    (distance as Cost).min(octile(node, end_node))
+}
+
+// The number of steps between two nodes when diagonal steps cost as much as straight ones.
+// Admissible on any connectivity, as diagonal steps never cost less than straight ones.
+pub fn chebyshev(node: &MazeNode, end_node: &MazeNode) -> Cost {
+    let (dx, dy) = deltas(node, end_node);
+    dx.max(dy) * STRAIGHT_STEP_COST
+}
+
+// The cost of the cheapest path between two nodes on an empty 8-connected maze.
+// Admissible on any connectivity, and exact on empty 8-connected mazes.
+pub fn octile(node: &MazeNode, end_node: &MazeNode) -> Cost {
+    Maze::octile_distance(node.get_coordinates(), end_node.get_coordinates())
+}
+
+// No estimate at all, which turns A* into Dijkstra's algorithm
+pub fn zero(_: &MazeNode, _: &MazeNode) -> Cost {
+    0
+}
+
+// Scales a heuristic by a weight. Weights above 1 make informed searchers greedier: they usually develop fewer nodes,
+// but the path found may cost up to `weight` times the optimal one.
+pub fn weighted<F: HeuristicFn>(heuristic: F, weight: f64) -> impl HeuristicFn {
+    move |node: &MazeNode, end_node: &MazeNode| (heuristic(node, end_node) as f64 * weight) as Cost
+}
+
+// The heuristic with the given name, if any
+pub fn from_name(name: &str) -> Option<Heuristic> {
+    match name {
+        "manhattan" => Some(manhattan),
+        "euclidean" => Some(euclidean),
+        "chebyshev" => Some(chebyshev),
+        "octile" => Some(octile),
+        "zero" => Some(zero),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, rc::Rc};
+
+    use super::{manhattan, weighted};
+    use crate::maze::Maze;
+    use crate::search::{a_star::AStarSearcher, dijkstra::DijkstraSearcher, heuristic_check};
+    use crate::test_utils::{load_example, solve, EXAMPLES};
+
+    #[test]
+    fn weighted_a_star_stays_within_the_weight() -> Result<(), Box<dyn Error>> {
+        for example in EXAMPLES {
+            let maze = Rc::new(Maze::try_from(load_example(example)?)?);
+            let optimal = solve(&mut DijkstraSearcher::new(maze.clone()))
+                .and_then(|path| path.cost())
+                .ok_or("Dijkstra should find a path")?;
+
+            for weight in [1., 1.5, 2., 5.] {
+                let heuristic = Box::new(weighted(manhattan, weight));
+                let cost = solve(&mut AStarSearcher::new(maze.clone(), heuristic))
+                    .and_then(|path| path.cost())
+                    .ok_or("weighted A* should find a path")?;
+                assert!(
+                    cost as f64 <= weight * optimal as f64,
+                    "{example} with weight {weight}: {cost} > {weight} * {optimal}"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn huge_weights_do_not_overflow() -> Result<(), Box<dyn Error>> {
+        let maze = Rc::new(Maze::try_from(load_example("10x10")?)?);
+        let heuristic = weighted(manhattan, f64::MAX);
+        assert!(solve(&mut AStarSearcher::new(maze.clone(), Box::new(&heuristic))).is_some());
+        assert!(!heuristic_check::check(&maze, &heuristic).is_admissible());
+        Ok(())
+    }
+}
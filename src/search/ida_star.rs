@@ -0,0 +1,201 @@
+use std::{cmp::Reverse, rc::Rc};
+
+use crate::maze::{Cost, Maze};
+
+use super::{a_star::HeuristicFn, path::Path, MazeNode, Searcher};
+
+// Each frame of the stack holds a node of the current path, and the neighbours of that node (with the cost of the
+// path up to them) that are still to be tried.
+struct Frame {
+    node: MazeNode,
+    children: Vec<(MazeNode, Cost)>,
+}
+
+// The IDA* searcher runs successive depth-first passes, each limited to paths whose cost + heuristic is at most `bound`.
+// The bound of the next pass is the lowest cost + heuristic that exceeded the bound of the current one. Only the current
+// path is stored, so memory stays proportional to the depth, while the path found is optimal for an admissible heuristic.
+pub struct IdaStarSearcher<F>
+where
+    F: HeuristicFn,
+{
+    maze: Rc<Maze>,
+    heuristic: Box<F>,
+    frames: Vec<Frame>,
+    bound: Cost,
+    iteration: usize,
+    // The lowest cost + heuristic skipped in the current pass, which will be the bound of the next pass
+    next_bound: Option<Cost>,
+}
+
+impl<F: HeuristicFn> IdaStarSearcher<F> {
+    pub fn new(maze: Rc<Maze>, heuristic: Box<F>) -> IdaStarSearcher<F> {
+        let bound = heuristic(&maze.get_start(), &maze.get_end());
+        IdaStarSearcher {
+            maze,
+            heuristic,
+            frames: Vec::new(),
+            bound,
+            iteration: 0,
+            next_bound: Some(bound),
+        }
+    }
+
+    // The maximum cost + heuristic of the paths explored in the current pass
+    pub fn bound(&self) -> Cost {
+        self.bound
+    }
+
+    // The number of passes started so far (the first pass is iteration 1)
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    // Builds the frame of a node added at the end of the current path, trying its neighbours only within the bound.
+    // The neighbours are sorted so that the one with the lowest cost + heuristic is tried first.
+    fn frame(&mut self, node: MazeNode, cost: Cost) -> Frame {
+        let end = self.maze.get_end();
+        let mut children: Vec<(MazeNode, Cost, Cost)> = node
+            .get_neighbors()
+            .into_iter()
+            .filter(|neighbour| !self.frames.iter().any(|frame| frame.node == *neighbour))
+            .filter_map(|neighbour| {
+                let new_cost = cost + node.step_cost(&neighbour)?;
+                let estimate = new_cost.saturating_add((self.heuristic)(&neighbour, &end));
+                Some((neighbour, new_cost, estimate))
+            })
+            .collect();
+
+        children.retain(|(_, _, estimate)| {
+            if *estimate <= self.bound {
+                return true;
+            }
+            self.next_bound = Some(self.next_bound.map_or(*estimate, |b| b.min(*estimate)));
+            false
+        });
+        children.sort_by_key(|(_, _, estimate)| Reverse(*estimate));
+
+        Frame {
+            node,
+            children: children
+                .into_iter()
+                .map(|(child, new_cost, _)| (child, new_cost))
+                .collect(),
+        }
+    }
+}
+
+impl<F: HeuristicFn> super::Searcher for IdaStarSearcher<F> {
+    // To get the current path, return the path to the node that will be developed next.
+    fn get_current_path(&self) -> Option<Path> {
+        let Some(depth) = self
+            .frames
+            .iter()
+            .rposition(|frame| !frame.children.is_empty())
+        else {
+            // The pass is over, so the next node is the start of a new pass (if any)
+            return self
+                .next_bound
+                .map(|_| [self.maze.get_start()].into_iter().collect());
+        };
+
+        Some(
+            self.frames[..=depth]
+                .iter()
+                .map(|frame| frame.node.clone())
+                .chain(
+                    self.frames[depth]
+                        .children
+                        .last()
+                        .map(|(child, _)| child.clone()),
+                )
+                .collect(),
+        )
+    }
+
+    // To get the considered nodes, return the neighbours still to be tried along the current path.
+    fn get_considered_nodes(&self) -> Vec<MazeNode> {
+        self.frames
+            .iter()
+            .flat_map(|frame| frame.children.iter().map(|(child, _)| child.clone()))
+            .collect()
+    }
+
+    // To develop the next node, backtrack until a node with neighbours left to try is found, and step into the best of them.
+    // When the pass is over and some path exceeded the bound, a new pass is started with the lowest exceeding bound.
+    fn develop_next_node(&mut self) -> Option<MazeNode> {
+        while self
+            .frames
+            .last()
+            .is_some_and(|frame| frame.children.is_empty())
+        {
+            self.frames.pop();
+        }
+
+        let (node, cost) = match self.frames.last_mut() {
+            Some(frame) => frame.children.pop()?,
+            None => {
+                self.bound = self.next_bound.take()?;
+                self.iteration += 1;
+                (self.maze.get_start(), 0)
+            }
+        };
+
+        let frame = self.frame(node.clone(), cost);
+        self.frames.push(frame);
+        Some(node)
+    }
+
+    fn get_iteration(&self) -> Option<usize> {
+        Some(self.iteration)
+    }
+}
+
+impl<F: HeuristicFn> Iterator for IdaStarSearcher<F> {
+    type Item = MazeNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.develop_next_node()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, rc::Rc};
+
+    use super::IdaStarSearcher;
+    use crate::maze::Maze;
+    use crate::search::{dijkstra::DijkstraSearcher, heuristics};
+    use crate::test_utils::{load_example, solve, EXAMPLES};
+    use crate::tilemap::TileMap;
+
+    fn assert_matches_dijkstra(maze: Rc<Maze>, context: &str) -> Result<(), Box<dyn Error>> {
+        let dijkstra = solve(&mut DijkstraSearcher::new(maze.clone()))
+            .and_then(|path| path.cost())
+            .ok_or("Dijkstra should find a path")?;
+        let mut searcher = IdaStarSearcher::new(maze, Box::new(heuristics::manhattan));
+        let ida_star = solve(&mut searcher)
+            .and_then(|path| path.cost())
+            .ok_or(format!("{context}: IDA* should find a path"))?;
+        assert_eq!(ida_star, dijkstra, "{context}");
+        // An admissible heuristic never lets a pass go past the optimal cost
+        assert!(searcher.bound() <= dijkstra, "{context}");
+        Ok(())
+    }
+
+    #[test]
+    fn matches_dijkstra_on_examples() -> Result<(), Box<dyn Error>> {
+        // Every pass starts over from the start tile, which takes too long on the larger examples
+        for example in &EXAMPLES[..3] {
+            assert_matches_dijkstra(Rc::new(Maze::try_from(load_example(example)?)?), example)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn goes_around_mud_when_it_is_cheaper() -> Result<(), Box<dyn Error>> {
+        let maze = Maze::try_from(TileMap::try_from(String::from(
+            "1111111\n1244431\n1000001\n1111111",
+        ))?)?;
+        assert_matches_dijkstra(Rc::new(maze), "muddy room")
+    }
+}
@@ -0,0 +1,180 @@
+use std::rc::Rc;
+
+use crate::maze::Maze;
+
+use super::{path::Path, MazeNode, Searcher};
+
+// Each frame of the stack holds a node of the current path and the neighbours of that node that are still to be tried.
+struct Frame {
+    node: MazeNode,
+    children: Vec<MazeNode>,
+}
+
+// The Iterative Deepening searcher runs successive depth-first passes, each limited to paths of at most `bound` steps,
+// increasing the bound after every pass that was cut short. Only the current path is stored, so memory stays
+// proportional to the depth, while the first path found to the end node is the shortest one (in number of steps).
+pub struct IterativeDeepeningSearcher {
+    maze: Rc<Maze>,
+    frames: Vec<Frame>,
+    bound: usize,
+    iteration: usize,
+    // Whether the current pass skipped a node because of the bound, in which case a deeper pass is needed
+    cutoff: bool,
+}
+
+impl IterativeDeepeningSearcher {
+    pub fn new(maze: Rc<Maze>) -> IterativeDeepeningSearcher {
+        IterativeDeepeningSearcher {
+            maze,
+            frames: Vec::new(),
+            bound: 0,
+            iteration: 0,
+            cutoff: true,
+        }
+    }
+
+    // The maximum number of steps of the paths explored in the current pass
+    pub fn bound(&self) -> usize {
+        self.bound
+    }
+
+    // The number of passes started so far (the first pass is iteration 1)
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    // Builds the frame of a node added at the end of the current path, trying its neighbours only within the bound.
+    fn frame(&mut self, node: MazeNode) -> Frame {
+        let neighbours: Vec<MazeNode> = node
+            .get_neighbors()
+            .into_iter()
+            .filter(|neighbour| !self.frames.iter().any(|frame| frame.node == *neighbour))
+            .collect();
+
+        // The depth of the node is the number of frames below it
+        let children = if self.frames.len() < self.bound {
+            neighbours
+        } else {
+            self.cutoff |= !neighbours.is_empty();
+            Vec::new()
+        };
+
+        Frame { node, children }
+    }
+}
+
+impl super::Searcher for IterativeDeepeningSearcher {
+    // To get the current path, return the path to the node that will be developed next.
+    fn get_current_path(&self) -> Option<Path> {
+        let Some(depth) = self
+            .frames
+            .iter()
+            .rposition(|frame| !frame.children.is_empty())
+        else {
+            // The pass is over, so the next node is the start of a new pass (if any)
+            return self
+                .cutoff
+                .then(|| [self.maze.get_start()].into_iter().collect());
+        };
+
+        Some(
+            self.frames[..=depth]
+                .iter()
+                .map(|frame| frame.node.clone())
+                .chain(self.frames[depth].children.last().cloned())
+                .collect(),
+        )
+    }
+
+    // To get the considered nodes, return the neighbours still to be tried along the current path.
+    fn get_considered_nodes(&self) -> Vec<MazeNode> {
+        self.frames
+            .iter()
+            .flat_map(|frame| frame.children.iter().cloned())
+            .collect()
+    }
+
+    // To develop the next node, backtrack until a node with neighbours left to try is found, and step into one of them.
+    // When the pass is over and it was cut short by the bound, a new pass is started with a bound one step deeper.
+    fn develop_next_node(&mut self) -> Option<MazeNode> {
+        while self
+            .frames
+            .last()
+            .is_some_and(|frame| frame.children.is_empty())
+        {
+            self.frames.pop();
+        }
+
+        let node = match self.frames.last_mut() {
+            Some(frame) => frame.children.pop()?,
+            None if self.cutoff => {
+                if self.iteration > 0 {
+                    self.bound += 1;
+                }
+                self.iteration += 1;
+                self.cutoff = false;
+                self.maze.get_start()
+            }
+            None => return None,
+        };
+
+        let frame = self.frame(node.clone());
+        self.frames.push(frame);
+        Some(node)
+    }
+
+    fn get_iteration(&self) -> Option<usize> {
+        Some(self.iteration)
+    }
+}
+
+impl Iterator for IterativeDeepeningSearcher {
+    type Item = MazeNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.develop_next_node()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, rc::Rc};
+
+    use super::IterativeDeepeningSearcher;
+    use crate::maze::Maze;
+    use crate::search::bfs::BreadthFirstSearcher;
+    use crate::test_utils::{load_example, solve, EXAMPLES};
+    use crate::tilemap::TileMap;
+
+    #[test]
+    fn finds_the_fewest_steps_like_bfs_on_examples() -> Result<(), Box<dyn Error>> {
+        // Every pass starts over from the start tile, which takes too long on the larger examples
+        for example in &EXAMPLES[..3] {
+            let maze = Rc::new(Maze::try_from(load_example(example)?)?);
+            let bfs =
+                solve(&mut BreadthFirstSearcher::new(&maze)).ok_or("BFS should find a path")?;
+            let mut searcher = IterativeDeepeningSearcher::new(maze.clone());
+            let iddfs =
+                solve(&mut searcher).ok_or(format!("{example}: IDDFS should find a path"))?;
+
+            assert_eq!(iddfs.len(), bfs.len(), "{example}");
+            assert_eq!(iddfs.cost(), bfs.cost(), "{example}");
+            // The path was found in the pass whose bound is its number of steps
+            assert_eq!(searcher.bound(), iddfs.len() - 1, "{example}");
+            assert_eq!(searcher.iteration(), iddfs.len(), "{example}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn stops_when_the_end_is_unreachable() -> Result<(), Box<dyn Error>> {
+        let maze = Rc::new(Maze::try_from(TileMap::try_from(String::from(
+            "1111111\n1200131\n1111111",
+        ))?)?);
+        let mut searcher = IterativeDeepeningSearcher::new(maze);
+        assert!(solve(&mut searcher).is_none());
+        // The last pass reached every cell without being cut short by its bound
+        assert_eq!(searcher.bound(), 2);
+        Ok(())
+    }
+}
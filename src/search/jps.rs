@@ -0,0 +1,362 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    rc::Rc,
+};
+
+use crate::{
+    maze::{Connectivity, Coordinates, CornerCutting, Cost, Maze},
+    tilemap::Tile,
+};
+
+use super::{
+    a_star::HeuristicFn,
+    path::{Path, PathArena, PathIdx},
+    MazeNode, Searcher,
+};
+
+// A unit step along one of the axes or diagonals, as (dx, dy)
+type Direction = (isize, isize);
+
+const UP: Direction = (0, -1);
+const LEFT: Direction = (-1, 0);
+const DOWN: Direction = (0, 1);
+const RIGHT: Direction = (1, 0);
+
+// The cell at the given offset from another, if the coordinates stay positive
+fn shift(coord: Coordinates, offset: Direction) -> Option<Coordinates> {
+    Some((
+        coord.0.checked_add_signed(offset.0)?,
+        coord.1.checked_add_signed(offset.1)?,
+    ))
+}
+
+// The unit direction from one cell to another on the same row, column or diagonal
+fn direction_between(from: Coordinates, to: Coordinates) -> Direction {
+    (
+        (to.0 as isize - from.0 as isize).signum(),
+        (to.1 as isize - from.1 as isize).signum(),
+    )
+}
+
+// An entry of the frontier: the priority (cost + heuristic, heuristic, insertion order), the path index and its cost
+type FrontierEntry = (Reverse<(Cost, Cost, u64)>, PathIdx, Cost);
+
+// The Jump Point searcher is an A* searcher over "jump points" only: instead of adding every neighbour to the frontier,
+// it scans along straight lines and only stops where the route may have to turn (a forced neighbour) or at the end node.
+// Paths are kept in canonical form, so symmetric routes through open areas are explored only once. On 4-connected mazes,
+// vertical moves come first and horizontal moves only continue straight unless forced; on 8-connected mazes, diagonal
+// moves come first, following the classic jump point rules (with or without corner cutting).
+// The pruning assumes every move has the same cost, so on weighted terrain the path found is the shortest in number
+// of steps, not necessarily the cheapest one.
+pub struct JumpPointSearcher<F>
+where
+    F: HeuristicFn,
+{
+    maze: Rc<Maze>,
+    arena: PathArena,
+    frontier: BinaryHeap<FrontierEntry>,
+    heuristic: Box<F>,
+    best_costs: HashMap<Coordinates, Cost>,
+    insertions: u64,
+}
+
+impl<F: HeuristicFn> JumpPointSearcher<F> {
+    pub fn new(maze: Rc<Maze>, heuristic: Box<F>) -> JumpPointSearcher<F> {
+        let start_node = maze.get_start();
+        let best_costs = [(start_node.get_coordinates(), 0)].into();
+
+        let mut arena = PathArena::new();
+        let initial_path = arena.push(start_node, None);
+
+        let mut searcher = JumpPointSearcher {
+            maze,
+            arena,
+            frontier: BinaryHeap::new(),
+            heuristic,
+            best_costs,
+            insertions: 0,
+        };
+        searcher.push(initial_path, 0);
+        searcher
+    }
+
+    // Adds a path to the frontier, computing the heuristic of its last node.
+    fn push(&mut self, path: PathIdx, cost: Cost) {
+        let Some(last) = self.arena.node(path) else {
+            return;
+        };
+        let heuristic = (self.heuristic)(last, &self.maze.get_end());
+
+        self.frontier.push((
+            Reverse((cost.saturating_add(heuristic), heuristic, self.insertions)),
+            path,
+            cost,
+        ));
+        self.insertions += 1;
+    }
+
+    // Whether the cell at the given offset from a cell is inside the maze and not a wall
+    fn is_walkable(&self, coord: Coordinates, offset: Direction) -> bool {
+        shift(coord, offset)
+            .and_then(|coord| self.maze.get_node(coord))
+            .is_some_and(|node| node.get_tile() != Tile::Wall)
+    }
+
+    // The coordinates one step away in the given direction, if the maze allows moving there
+    fn step(&self, coord: Coordinates, direction: Direction) -> Option<Coordinates> {
+        let next = shift(coord, direction)?;
+        self.maze.step_cost(coord, next).map(|_| next)
+    }
+
+    // Whether the scan must stop at a cell reached in the given direction, as some route may have to turn there.
+    fn is_jump_point(&self, coord: Coordinates, direction: Direction) -> bool {
+        let (dx, dy) = direction;
+        let walkable = |x: isize, y: isize| self.is_walkable(coord, (x, y));
+        // One of the two directions perpendicular to a straight move
+        let (sx, sy) = (dy, dx);
+        let diagonal = dx != 0 && dy != 0;
+
+        match self.maze.connectivity() {
+            // Horizontal scans stop where a side cell is free while the same side of the previous cell is blocked,
+            // and vertical scans stop where a horizontal scan finds a jump point
+            Connectivity::Four if dy == 0 => {
+                (walkable(sx, sy) && !walkable(sx - dx, sy - dy))
+                    || (walkable(-sx, -sy) && !walkable(-sx - dx, -sy - dy))
+            }
+            Connectivity::Four => {
+                self.jump(coord, LEFT).is_some() || self.jump(coord, RIGHT).is_some()
+            }
+            // Diagonal scans stop where a straight scan along either component finds a jump point,
+            // and straight scans stop where a side cell is free while the same side of the previous cell is blocked
+            Connectivity::Eight(CornerCutting::Never) if diagonal => {
+                self.jump(coord, (dx, 0)).is_some() || self.jump(coord, (0, dy)).is_some()
+            }
+            Connectivity::Eight(CornerCutting::Never) => {
+                (walkable(sx, sy) && !walkable(sx - dx, sy - dy))
+                    || (walkable(-sx, -sy) && !walkable(-sx - dx, -sy - dy))
+            }
+            // When corners can be cut, scans also stop next to the corner of a wall, as the route may turn around it
+            Connectivity::Eight(CornerCutting::Always) if diagonal => {
+                (walkable(-dx, dy) && !walkable(-dx, 0))
+                    || (walkable(dx, -dy) && !walkable(0, -dy))
+                    || self.jump(coord, (dx, 0)).is_some()
+                    || self.jump(coord, (0, dy)).is_some()
+            }
+            Connectivity::Eight(CornerCutting::Always) => {
+                (walkable(dx + sx, dy + sy) && !walkable(sx, sy))
+                    || (walkable(dx - sx, dy - sy) && !walkable(-sx, -sy))
+            }
+        }
+    }
+
+    // Scans from a cell in the given direction, and returns the first jump point found, if any.
+    fn jump(&self, coord: Coordinates, direction: Direction) -> Option<Coordinates> {
+        let end = self.maze.get_end().get_coordinates();
+        let mut current = coord;
+        loop {
+            current = self.step(current, direction)?;
+            if current == end || self.is_jump_point(current, direction) {
+                return Some(current);
+            }
+        }
+    }
+
+    // The directions to scan from a jump point, given the direction it was reached from (None for the start node):
+    // the natural directions that continue the move, plus the forced ones that turn around an obstacle.
+    fn scan_directions(&self, coord: Coordinates, arrival: Option<Direction>) -> Vec<Direction> {
+        let walkable = |x: isize, y: isize| self.is_walkable(coord, (x, y));
+
+        let candidates: Vec<Direction> = match (self.maze.connectivity(), arrival) {
+            (Connectivity::Four, None) => vec![UP, LEFT, DOWN, RIGHT],
+            (Connectivity::Eight(_), None) => {
+                vec![UP, LEFT, DOWN, RIGHT, (-1, -1), (-1, 1), (1, 1), (1, -1)]
+            }
+            (Connectivity::Four, Some((0, dy))) => vec![(0, dy), LEFT, RIGHT],
+            (Connectivity::Four, Some((dx, _))) => [(dx, 0), UP, DOWN]
+                .into_iter()
+                .filter(|(x, y)| *x != 0 || (walkable(0, *y) && !walkable(-dx, *y)))
+                .collect(),
+            (Connectivity::Eight(CornerCutting::Never), Some((dx, dy))) if dx != 0 && dy != 0 => {
+                vec![(0, dy), (dx, 0), (dx, dy)]
+            }
+            (Connectivity::Eight(CornerCutting::Never), Some((dx, dy))) => {
+                let (sx, sy) = (dy, dx);
+                vec![
+                    (dx, dy),
+                    (dx + sx, dy + sy),
+                    (dx - sx, dy - sy),
+                    (sx, sy),
+                    (-sx, -sy),
+                ]
+            }
+            (Connectivity::Eight(CornerCutting::Always), Some((dx, dy))) if dx != 0 && dy != 0 => {
+                let mut directions = vec![(0, dy), (dx, 0), (dx, dy)];
+                if !walkable(-dx, 0) {
+                    directions.push((-dx, dy));
+                }
+                if !walkable(0, -dy) {
+                    directions.push((dx, -dy));
+                }
+                directions
+            }
+            (Connectivity::Eight(CornerCutting::Always), Some((dx, dy))) => {
+                let (sx, sy) = (dy, dx);
+                let mut directions = vec![(dx, dy)];
+                if !walkable(sx, sy) {
+                    directions.push((dx + sx, dy + sy));
+                }
+                if !walkable(-sx, -sy) {
+                    directions.push((dx - sx, dy - sy));
+                }
+                directions
+            }
+        };
+
+        candidates
+            .into_iter()
+            .filter(|direction| self.step(coord, *direction).is_some())
+            .collect()
+    }
+
+    // Drops the outdated entries on top of the frontier, so that the top entry is always the next one to be developed.
+    fn discard_outdated(&mut self) {
+        while let Some((_, path, cost)) = self.frontier.peek() {
+            let outdated = self
+                .arena
+                .node(*path)
+                .and_then(|node| self.best_costs.get(&node.get_coordinates()))
+                .is_some_and(|best| best < cost);
+            if !outdated {
+                break;
+            }
+            self.frontier.pop();
+        }
+    }
+
+    // Rebuilds the full path, filling the straight lines between consecutive jump points cell by cell.
+    fn expand_path(&self, path: PathIdx) -> Path {
+        let mut jump_points: Vec<&MazeNode> = self.arena.ancestors(path).collect();
+        jump_points.reverse();
+
+        let mut nodes: Vec<MazeNode> = jump_points
+            .first()
+            .map(|node| (*node).clone())
+            .into_iter()
+            .collect();
+        for segment in jump_points.windows(2) {
+            let (from, to) = (segment[0].get_coordinates(), segment[1].get_coordinates());
+            let direction = direction_between(from, to);
+            let mut current = from;
+            while let Some(next) = shift(current, direction).filter(|_| current != to) {
+                current = next;
+                nodes.extend(self.maze.get_node(current));
+            }
+        }
+        nodes.into_iter().collect()
+    }
+}
+
+impl<F: HeuristicFn> super::Searcher for JumpPointSearcher<F> {
+    // To get the current path, return the path with the lowest cost + heuristic, expanded cell by cell.
+    fn get_current_path(&self) -> Option<Path> {
+        self.frontier
+            .peek()
+            .map(|(_, path, _)| self.expand_path(*path))
+    }
+
+    // To get the considered nodes, return the jump points in the frontier.
+    fn get_considered_nodes(&self) -> Vec<MazeNode> {
+        self.frontier
+            .iter()
+            .filter_map(|(_, path, _)| self.arena.node(*path).cloned())
+            .collect()
+    }
+
+    // To develop the next node, take the path with the lowest cost + heuristic, and scan from its last jump point
+    // in every direction allowed by the canonical ordering, adding the jump points found to the frontier.
+    fn develop_next_node(&mut self) -> Option<MazeNode> {
+        self.discard_outdated();
+        let (_, path, cost) = self.frontier.pop()?;
+
+        let node = self.arena.node(path)?.clone();
+        let coord = node.get_coordinates();
+        let arrival = self
+            .arena
+            .parent(path)
+            .and_then(|parent| self.arena.node(parent))
+            .map(|parent| direction_between(parent.get_coordinates(), coord));
+
+        for direction in self.scan_directions(coord, arrival) {
+            let Some(jump_point) = self.jump(coord, direction) else {
+                continue;
+            };
+            // Jumps go along a single direction, so their cost is the octile distance on uniform-cost terrain
+            let new_cost = cost + Maze::octile_distance(coord, jump_point);
+            if self
+                .best_costs
+                .get(&jump_point)
+                .is_some_and(|best| *best <= new_cost)
+            {
+                continue;
+            }
+            self.best_costs.insert(jump_point, new_cost);
+
+            let Some(jump_node) = self.maze.get_node(jump_point) else {
+                continue;
+            };
+            let new_path = self.arena.push(jump_node, Some(path));
+            self.push(new_path, new_cost);
+        }
+
+        self.discard_outdated();
+        Some(node)
+    }
+}
+
+impl<F: HeuristicFn> Iterator for JumpPointSearcher<F> {
+    type Item = MazeNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.develop_next_node()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, rc::Rc};
+
+    use super::JumpPointSearcher;
+    use crate::maze::{Connectivity, CornerCutting, Maze};
+    use crate::search::{bfs::BreadthFirstSearcher, heuristics};
+    use crate::test_utils::{load_example, solve, EXAMPLES};
+
+    const CONNECTIVITIES: [Connectivity; 3] = [
+        Connectivity::Four,
+        Connectivity::Eight(CornerCutting::Never),
+        Connectivity::Eight(CornerCutting::Always),
+    ];
+
+    #[test]
+    fn matches_bfs_on_examples() -> Result<(), Box<dyn Error>> {
+        for example in EXAMPLES {
+            for connectivity in CONNECTIVITIES {
+                let maze = Rc::new(
+                    Maze::try_from(load_example(example)?)?.with_connectivity(connectivity),
+                );
+                let jps = solve(&mut JumpPointSearcher::new(
+                    maze.clone(),
+                    Box::new(heuristics::octile),
+                ));
+                let bfs = solve(&mut BreadthFirstSearcher::new(&maze));
+
+                let (Some(jps), Some(bfs)) = (jps, bfs) else {
+                    panic!("{example} {connectivity:?}: both searchers should find a path");
+                };
+                assert_eq!(jps.len(), bfs.len(), "{example} {connectivity:?}");
+                assert_eq!(jps.cost(), bfs.cost(), "{example} {connectivity:?}");
+            }
+        }
+        Ok(())
+    }
+}
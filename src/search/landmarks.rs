@@ -0,0 +1,305 @@
+use std::{collections::VecDeque, rc::Rc};
+
+use crate::{
+    maze::{Connectivity, Coordinates, CornerCutting, Cost, Maze, STRAIGHT_STEP_COST},
+    npy::{self, NpyArray},
+    tilemap::Tile,
+};
+
+// The key of the fingerprint of the maze the cached landmarks were selected on, in the comment of the NPY header
+const MAZE_HASH_KEY: &str = "maze-hash";
+
+// The Landmarks struct stores a few landmark cells, and the exact number of steps from each landmark to every cell of
+// the maze (None for the cells it cannot reach), computed by a breadth-first search over the maze's connectivity.
+// By the triangle inequality, for any landmark L, d(n, g) >= |d(L, n) - d(L, g)|, so the largest such difference over
+// all landmarks is a lower bound of the number of steps between two cells. Every step costs at least
+// STRAIGHT_STEP_COST, whatever the terrain and direction, so the bound scaled by it is an admissible heuristic.
+// Unlike Manhattan distance, this bound accounts for the walls, which makes it far better informed on mazes with long detours.
+pub struct Landmarks {
+    width: usize,
+    height: usize,
+    // The fingerprint of the maze and connectivity the distances were computed on
+    maze_hash: u64,
+    landmarks: Vec<Coordinates>,
+    distances: Vec<Vec<Option<usize>>>,
+}
+
+impl Landmarks {
+    // Selects up to `count` landmarks by farthest-point sampling among the cells reachable from the start node: the
+    // first landmark is the cell farthest from the start node, and each following one is the cell farthest from all
+    // the landmarks chosen so far. Landmarks on the edges of the maze tend to give the best estimates.
+    pub fn select(maze: &Rc<Maze>, count: usize) -> Landmarks {
+        let width = maze.width();
+        let mut landmarks = Vec::new();
+        let mut distances: Vec<Vec<Option<usize>>> = Vec::new();
+        // The distance from each cell to its closest landmark (to the start node before any landmark is chosen)
+        let mut closest = distances_from(maze, maze.get_start().get_coordinates());
+
+        while landmarks.len() < count {
+            let Some((idx, _)) = closest
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, distance)| distance.map(|distance| (idx, distance)))
+                .filter(|(_, distance)| *distance > 0)
+                .max_by_key(|(idx, distance)| (*distance, std::cmp::Reverse(*idx)))
+            else {
+                break;
+            };
+
+            let landmark = (idx % width, idx / width);
+            let from_landmark = distances_from(maze, landmark);
+            for (closest, distance) in closest.iter_mut().zip(&from_landmark) {
+                *closest = closest.zip(*distance).map(|(a, b)| a.min(b));
+            }
+            landmarks.push(landmark);
+            distances.push(from_landmark);
+        }
+
+        Landmarks {
+            width,
+            height: maze.height(),
+            maze_hash: maze_hash(maze),
+            landmarks,
+            distances,
+        }
+    }
+
+    // The largest number of landmarks `select` can find: one per cell reachable from the start node, other than the
+    // start node itself
+    pub fn max_count(maze: &Rc<Maze>) -> usize {
+        distances_from(maze, maze.get_start().get_coordinates())
+            .iter()
+            .filter(|distance| distance.is_some_and(|distance| distance > 0))
+            .count()
+    }
+
+    pub fn landmarks(&self) -> &[Coordinates] {
+        &self.landmarks
+    }
+
+    // The lower bound of the cost of going from one cell to another given by the triangle inequality.
+    // Landmarks that cannot reach both cells give no information, and are skipped.
+    pub fn estimate(&self, from: Coordinates, to: Coordinates) -> Cost {
+        let (from, to) = (from.1 * self.width + from.0, to.1 * self.width + to.0);
+        self.distances
+            .iter()
+            .filter_map(|distances| {
+                let (from, to) = (distances.get(from)?.as_ref()?, distances.get(to)?.as_ref()?);
+                Some(usize::abs_diff(*from, *to))
+            })
+            .max()
+            .unwrap_or_default() as Cost
+            * STRAIGHT_STEP_COST
+    }
+
+    // Serializes the landmarks as a NPY array with one row per landmark: its x and y coordinates, followed by the
+    // distance to every cell in row-major order (-1 for the cells it cannot reach). The fingerprint of the maze is
+    // saved in the header.
+    pub fn to_npy(&self) -> Vec<u8> {
+        let data = self
+            .landmarks
+            .iter()
+            .zip(&self.distances)
+            .flat_map(|(landmark, distances)| {
+                [landmark.0 as i64, landmark.1 as i64].into_iter().chain(
+                    distances
+                        .iter()
+                        .map(|distance| distance.map_or(-1, |distance| distance as i64)),
+                )
+            })
+            .collect();
+
+        NpyArray {
+            rows: self.landmarks.len(),
+            cols: 2 + self.width * self.height,
+            data,
+        }
+        .to_bytes_with_comment(&maze_hash_comment(self.maze_hash))
+    }
+
+    // Reads landmarks serialized by to_npy. Returns None if the bytes cannot be read, or if they do not match the
+    // maze (another fingerprint, wrong size, a wall that is reachable, a landmark that is not at distance 0 from
+    // itself), in which case the landmarks should be selected again. Any change to the maze or its connectivity
+    // changes the fingerprint, as distances computed before the change could overestimate the new ones.
+    pub fn from_npy(maze: &Rc<Maze>, bytes: &[u8]) -> Option<Landmarks> {
+        let maze_hash = maze_hash(maze);
+        if npy::header_comment(bytes).ok()? != Some(maze_hash_comment(maze_hash)) {
+            return None;
+        }
+
+        let array = NpyArray::try_from(bytes).ok()?;
+        let (width, height) = (maze.width(), maze.height());
+        if array.cols != 2 + width * height {
+            return None;
+        }
+
+        let mut landmarks = Vec::new();
+        let mut distances = Vec::new();
+        for row in array.data.chunks(array.cols) {
+            let landmark = (usize::try_from(row[0]).ok()?, usize::try_from(row[1]).ok()?);
+            let from_landmark: Vec<Option<usize>> = row[2..]
+                .iter()
+                .map(|distance| usize::try_from(*distance).ok())
+                .collect();
+
+            let matches_maze = from_landmark.iter().enumerate().all(|(idx, distance)| {
+                let tile = maze.tilemap().get(idx % width, idx / width);
+                distance.is_none() || tile.is_some_and(|tile| tile != Tile::Wall)
+            });
+            if landmark.0 >= width
+                || landmark.1 >= height
+                || !matches_maze
+                || from_landmark.get(landmark.1 * width + landmark.0) != Some(&Some(0))
+            {
+                return None;
+            }
+
+            landmarks.push(landmark);
+            distances.push(from_landmark);
+        }
+
+        Some(Landmarks {
+            width,
+            height,
+            maze_hash,
+            landmarks,
+            distances,
+        })
+    }
+}
+
+// A fingerprint of the tiles and connectivity of the maze (64-bit FNV-1a), which is the same on every run and platform
+fn maze_hash(maze: &Maze) -> u64 {
+    let connectivity = match maze.connectivity() {
+        Connectivity::Four => 0,
+        Connectivity::Eight(CornerCutting::Never) => 1,
+        Connectivity::Eight(CornerCutting::Always) => 2,
+    };
+    let tilemap = maze.tilemap();
+    let tiles = (0..tilemap.height())
+        .flat_map(|y| (0..tilemap.width()).map(move |x| (x, y)))
+        .map(|(x, y)| tilemap.get(x, y).map_or(-1, i64::from));
+
+    [maze.width() as i64, maze.height() as i64, connectivity]
+        .into_iter()
+        .chain(tiles)
+        .flat_map(i64::to_le_bytes)
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+// The comment of the NPY header holding the fingerprint of the maze
+fn maze_hash_comment(maze_hash: u64) -> String {
+    format!("{MAZE_HASH_KEY}: {maze_hash:016x}")
+}
+
+// The number of steps from a cell to every cell of the maze, in row-major order (None for the cells it cannot reach)
+fn distances_from(maze: &Rc<Maze>, coord: Coordinates) -> Vec<Option<usize>> {
+    let width = maze.width();
+    let mut distances = vec![None; width * maze.height()];
+    let Some(source) = maze.get_node(coord) else {
+        return distances;
+    };
+
+    distances[coord.1 * width + coord.0] = Some(0);
+    let mut queue = VecDeque::from([(source, 0)]);
+    while let Some((node, distance)) = queue.pop_front() {
+        for neighbour in node.get_neighbors() {
+            let (x, y) = neighbour.get_coordinates();
+            if distances[y * width + x].is_none() {
+                distances[y * width + x] = Some(distance + 1);
+                queue.push_back((neighbour, distance + 1));
+            }
+        }
+    }
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, rc::Rc};
+
+    use super::Landmarks;
+    use crate::maze::{Connectivity, CornerCutting, Maze};
+    use crate::npy::{self, NpyArray};
+    use crate::tilemap::{Tile, TileMap};
+
+    const MAZE: &str = "1111111\n1200101\n1110101\n1000031\n1111111";
+    // The same maze, with the wall at (4, 1) removed
+    const OPENED_MAZE: &str = "1111111\n1200001\n1110101\n1000031\n1111111";
+
+    fn maze(text: &str) -> Result<Maze, Box<dyn Error>> {
+        Ok(Maze::try_from(TileMap::try_from(text.to_string())?)?)
+    }
+
+    #[test]
+    fn cache_round_trip() -> Result<(), Box<dyn Error>> {
+        let maze = Rc::new(maze(MAZE)?);
+        let landmarks = Landmarks::select(&maze, 2);
+        let cached = Landmarks::from_npy(&maze, &landmarks.to_npy());
+        assert_eq!(
+            cached.map(|cached| cached.landmarks().to_vec()),
+            Some(landmarks.landmarks().to_vec())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn selects_at_most_one_landmark_per_reachable_cell() -> Result<(), Box<dyn Error>> {
+        let maze = Rc::new(maze(MAZE)?);
+        assert_eq!(Landmarks::max_count(&maze), 10);
+        assert_eq!(Landmarks::select(&maze, 100).landmarks().len(), 10);
+        Ok(())
+    }
+
+    #[test]
+    fn cache_rejected_when_a_wall_is_removed() -> Result<(), Box<dyn Error>> {
+        let bytes = Landmarks::select(&Rc::new(maze(MAZE)?), 2).to_npy();
+        assert!(Landmarks::from_npy(&Rc::new(maze(OPENED_MAZE)?), &bytes).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn cache_rejected_for_another_connectivity() -> Result<(), Box<dyn Error>> {
+        let bytes = Landmarks::select(&Rc::new(maze(MAZE)?), 2).to_npy();
+        let diagonal =
+            Rc::new(maze(MAZE)?.with_connectivity(Connectivity::Eight(CornerCutting::Never)));
+        assert!(Landmarks::from_npy(&diagonal, &bytes).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn cache_rejected_for_a_landmark_out_of_the_maze() -> Result<(), Box<dyn Error>> {
+        let maze = Rc::new(maze(MAZE)?);
+        let comment = npy::header_comment(&Landmarks::select(&maze, 1).to_npy())?
+            .ok_or("the cache should hold the fingerprint of the maze")?;
+
+        // Distances that match the maze (the walls cannot be reached, and every other cell is at distance 0), so that
+        // only the landmark itself can get the cache rejected
+        let tilemap = maze.tilemap();
+        let distances: Vec<i64> = (0..tilemap.height())
+            .flat_map(|y| (0..tilemap.width()).map(move |x| (x, y)))
+            .map(|(x, y)| match tilemap.get(x, y) {
+                Some(Tile::Wall) => -1,
+                _ => 0,
+            })
+            .collect();
+        let cache = |landmark_y: i64| {
+            let data = [1, landmark_y]
+                .into_iter()
+                .chain(distances.clone())
+                .collect();
+            let array = NpyArray {
+                rows: 1,
+                cols: 2 + maze.width() * maze.height(),
+                data,
+            };
+            array.to_bytes_with_comment(&comment)
+        };
+
+        assert!(Landmarks::from_npy(&maze, &cache(1)).is_some());
+        assert!(Landmarks::from_npy(&maze, &cache(i64::MAX)).is_none());
+        Ok(())
+    }
+}
@@ -1,12 +1,95 @@
 pub mod a_star;
+pub mod ara_star;
+pub mod beam;
 pub mod bfs;
+pub mod bidirectional_bfs;
+pub mod d_star_lite;
+pub mod dead_end_filling;
 pub mod dfs;
+pub mod dijkstra;
+pub mod greedy;
+pub mod heuristic_check;
+pub mod heuristics;
+pub mod ida_star;
+pub mod iddfs;
+pub mod jps;
+pub mod landmarks;
 pub mod path;
+pub mod tremaux;
+pub mod wall_follower;
+pub mod weighted_a_star;
+
+use std::fmt::{Display, Formatter};
 
 pub use crate::maze::MazeNode;
 
 pub trait Searcher: Iterator<Item = MazeNode> {
     fn get_considered_nodes(&self) -> Vec<MazeNode>;
     fn develop_next_node(&mut self) -> Option<MazeNode>;
-    fn get_current_path(&self) -> Option<&path::Path>;
+    fn get_current_path(&self) -> Option<path::Path>;
+
+    // Searchers that run successive passes over the maze (such as iterative deepening) return the number of the
+    // current pass, so that each pass can be shown separately.
+    fn get_iteration(&self) -> Option<usize> {
+        None
+    }
+
+    // Searchers that may return a suboptimal path return a bound on how far from optimal it can be: the cost of the
+    // path found is at most the bound times the cost of the optimal path.
+    fn get_suboptimality_bound(&self) -> Option<f64> {
+        None
+    }
+
+    // Anytime searchers find a first solution quickly, and keep searching for better ones afterwards. They return the
+    // best solution found so far, so that each improvement can be shown while the search goes on.
+    fn get_solution(&self) -> Option<path::Path> {
+        None
+    }
+
+    // Incomplete searchers may stop without reaching the end node even though a path exists. Once they stop, they
+    // return the reason why, so that it is not mistaken for the maze having no solution.
+    fn get_failure(&self) -> Option<SearchFailure> {
+        None
+    }
+
+    // Solvers that prune the maze (such as dead-end filling) develop nodes by filling them in rather than by searching
+    // through them, so that the nodes they develop can be shown as filled instead of visited.
+    fn fills_developed_nodes(&self) -> bool {
+        false
+    }
+}
+
+// Graph search keeps track of the best known cost of every cell, so each cell is only expanded again if a cheaper
+// route to it is found. Tree search only avoids cycles within a single path, so a cell is expanded once per distinct
+// route reaching it, which grows exponentially on open mazes but is useful for teaching purposes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    #[default]
+    Graph,
+    Tree,
+}
+
+// The reasons why an incomplete searcher may fail to find an existing path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchFailure {
+    // Paths were dropped to keep the frontier within the given width, and the paths left all led to dead ends. The
+    // number of cells whose paths were all dropped is given.
+    Pruned { pruned: usize, width: usize },
+    // A runner came back to a state it had already been in, and would walk the same loop forever
+    Loop { steps: usize },
+}
+
+impl Display for SearchFailure {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            SearchFailure::Pruned { pruned, width } => write!(
+                f,
+                "The search ran out of paths after dropping the paths to {pruned} cells to keep a width of {width}. A wider beam may find a path"
+            ),
+            SearchFailure::Loop { steps } => write!(
+                f,
+                "The runner started walking in circles after {steps} steps. The end may be on an island of walls the runner never touches"
+            ),
+        }
+    }
 }
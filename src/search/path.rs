@@ -1,6 +1,6 @@
 use std::collections::{vec_deque::Iter, VecDeque};
 
-use crate::maze::MazeNode;
+use crate::maze::{Cost, MazeNode};
 
 #[derive(Clone, Debug)]
 pub struct Path(VecDeque<MazeNode>);
@@ -21,40 +21,177 @@ impl Path {
         self.0.back()
     }
 
-    pub fn iter(&self) -> Iter<MazeNode> {
+    pub fn iter(&self) -> Iter<'_, MazeNode> {
         self.0.iter()
     }
 
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     pub fn contains(&self, node: &MazeNode) -> bool {
         self.0.contains(node)
     }
 
-    // The deepen_path method is used to create new paths from the current path.
-    pub fn deepen_path(self) -> Vec<Path> {
-        // Get the last node in the path, if none return itself
-        let Some(node) = self.0.back() else {
-            return [self].into();
+    // The total cost of walking the path, or None if two consecutive nodes are not adjacent
+    pub fn cost(&self) -> Option<Cost> {
+        self.0
+            .iter()
+            .zip(self.0.iter().skip(1))
+            .map(|(from, to)| from.step_cost(to))
+            .sum()
+    }
+}
+
+impl Default for Path {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FromIterator<MazeNode> for Path {
+    fn from_iter<T: IntoIterator<Item = MazeNode>>(iter: T) -> Self {
+        Path(iter.into_iter().collect())
+    }
+}
+
+// Index of a path stored in a PathArena
+pub type PathIdx = usize;
+
+// The PathArena stores paths as a node plus the index of the path it extends (its parent), so that paths sharing
+// a prefix share their storage. Searchers keep indices into the arena, and rebuild a full Path only when needed.
+#[derive(Debug, Default)]
+pub struct PathArena(Vec<(MazeNode, Option<PathIdx>)>);
+
+impl PathArena {
+    pub fn new() -> PathArena {
+        PathArena(Vec::new())
+    }
+
+    // Stores the path made of the parent path followed by the given node, and returns its index.
+    pub fn push(&mut self, node: MazeNode, parent: Option<PathIdx>) -> PathIdx {
+        self.0.push((node, parent));
+        self.0.len() - 1
+    }
+
+    // The last node of the path
+    pub fn node(&self, idx: PathIdx) -> Option<&MazeNode> {
+        self.0.get(idx).map(|(node, _)| node)
+    }
+
+    pub fn parent(&self, idx: PathIdx) -> Option<PathIdx> {
+        self.0.get(idx).and_then(|(_, parent)| *parent)
+    }
+
+    // Iterates over the nodes of the path, from its last node back to the first one.
+    pub fn ancestors(&self, idx: PathIdx) -> impl Iterator<Item = &MazeNode> + '_ {
+        std::iter::successors(self.0.get(idx), |(_, parent)| {
+            parent.and_then(|parent| self.0.get(parent))
+        })
+        .map(|(node, _)| node)
+    }
+
+    pub fn contains(&self, idx: PathIdx, node: &MazeNode) -> bool {
+        self.ancestors(idx).any(|ancestor| ancestor == node)
+    }
+
+    // The number of nodes in the path
+    pub fn path_len(&self, idx: PathIdx) -> usize {
+        self.ancestors(idx).count()
+    }
+
+    // Rebuilds the full path, from the first node to the last one.
+    pub fn path(&self, idx: PathIdx) -> Path {
+        let mut nodes: Vec<MazeNode> = self.ancestors(idx).cloned().collect();
+        nodes.reverse();
+        nodes.into_iter().collect()
+    }
+
+    // The deepen method is used to create new paths from the path at the given index, one for each neighbour of
+    // its last node that is not already in the path. Returns the indices of the new paths.
+    pub fn deepen(&mut self, idx: PathIdx) -> Vec<PathIdx> {
+        // Get the last node in the path, if none there is nothing to deepen
+        let Some(node) = self.node(idx) else {
+            return Vec::new();
         };
 
-        let neighbors = node.get_neighbors();
-        let next_nodes: Vec<MazeNode> = neighbors
+        let next_nodes: Vec<MazeNode> = node
+            .get_neighbors()
             .into_iter()
-            .filter(|node| !self.contains(node))
+            .filter(|node| !self.contains(idx, node))
             .collect();
 
-        let mut new_paths = Vec::new();
-        next_nodes.into_iter().for_each(|node| {
-            let mut new_path = self.clone();
-            new_path.push(node);
-            new_paths.push(new_path);
-        });
-
-        new_paths
+        next_nodes
+            .into_iter()
+            .map(|node| self.push(node, Some(idx)))
+            .collect()
     }
 }
 
-impl Default for Path {
-    fn default() -> Self {
-        Self::new()
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, rc::Rc};
+
+    use super::{Path, PathArena};
+    use crate::maze::{Maze, MazeNode};
+    use crate::tilemap::TileMap;
+
+    fn coordinates(path: &Path) -> Vec<(usize, usize)> {
+        path.iter().map(MazeNode::get_coordinates).collect()
+    }
+
+    #[test]
+    fn paths_sharing_a_prefix_are_rebuilt_from_their_parents() -> Result<(), Box<dyn Error>> {
+        let maze = Rc::new(Maze::try_from(TileMap::try_from(String::from(
+            "11111\n12001\n10031\n11111",
+        ))?)?);
+        let mut arena = PathArena::new();
+        let start = arena.push(maze.get_start(), None);
+
+        // The start tile has two open neighbours, each starting a path
+        let children = arena.deepen(start);
+        assert_eq!(children.len(), 2);
+        for child in &children {
+            assert_eq!(arena.parent(*child), Some(start));
+            assert_eq!(arena.path_len(*child), 2);
+        }
+
+        let right = *children
+            .iter()
+            .find(|child| arena.node(**child).map(MazeNode::get_coordinates) == Some((2, 1)))
+            .ok_or("no path to the right of the start tile")?;
+        let step_cost = arena
+            .path(right)
+            .cost()
+            .ok_or("the first step has no cost")?;
+        let grandchildren = arena.deepen(right);
+        // The start tile is already on the path, so it is not reached again
+        assert_eq!(grandchildren.len(), 2);
+        for grandchild in grandchildren {
+            let path = arena.path(grandchild);
+            assert_eq!(coordinates(&path)[..2], [(1, 1), (2, 1)]);
+            assert_eq!(path.cost(), Some(2 * step_cost));
+            assert!(!arena.contains(right, &path.last().cloned().ok_or("empty path")?));
+        }
+        assert_eq!(coordinates(&arena.path(start)), [(1, 1)]);
+        Ok(())
+    }
+
+    #[test]
+    fn paths_with_gaps_have_no_cost() -> Result<(), Box<dyn Error>> {
+        let maze = Rc::new(Maze::try_from(TileMap::try_from(String::from(
+            "11111\n12001\n10031\n11111",
+        ))?)?);
+        let path: Path = [(1, 1), (3, 1)]
+            .into_iter()
+            .filter_map(|coord| maze.get_node(coord))
+            .collect();
+        assert_eq!(path.len(), 2);
+        assert_eq!(path.cost(), None);
+        Ok(())
     }
 }
@@ -0,0 +1,155 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::maze::{Coordinates, Maze};
+
+use super::{
+    path::Path,
+    wall_follower::{Facing, Runner},
+    MazeNode, Searcher,
+};
+
+// The Trémaux searcher walks through the maze marking each passage (here, each move between two cells) every time
+// it goes through it, and never goes through a passage marked twice:
+// - when reaching a cell for the first time, it takes any unmarked passage (turning left first, then going forward,
+//   then turning right), or turns back on a dead end;
+// - when reaching an already visited cell through a new passage, it turns back;
+// - otherwise, it takes the passage with the fewest marks.
+// Unlike wall followers, it always finds the end node if there is a path to it, and the passages marked once then
+// form a direct path from the start node. If there is none, it ends up back at the start node with every passage
+// marked twice.
+pub struct TremauxSearcher {
+    runner: Runner,
+    marks: HashMap<(Coordinates, Coordinates), u8>,
+    stuck: bool,
+    done: bool,
+}
+
+impl TremauxSearcher {
+    pub fn new(maze: Rc<Maze>) -> TremauxSearcher {
+        TremauxSearcher {
+            runner: Runner::new(maze),
+            marks: HashMap::new(),
+            stuck: false,
+            done: false,
+        }
+    }
+
+    fn passage(from: Coordinates, to: Coordinates) -> (Coordinates, Coordinates) {
+        (from.min(to), from.max(to))
+    }
+
+    // The number of times the runner went through the passage between two cells
+    pub fn marks(&self, from: Coordinates, to: Coordinates) -> u8 {
+        self.marks
+            .get(&TremauxSearcher::passage(from, to))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn facing(&self) -> Facing {
+        self.runner.facing
+    }
+
+    // The number of steps walked so far, including the ones erased from the route
+    pub fn steps(&self) -> usize {
+        self.runner.steps
+    }
+
+    // The directions the runner can move to with the marks of their passage, turning left first
+    fn passages(&self) -> Vec<(Facing, u8)> {
+        let facing = self.runner.facing;
+        [
+            facing.turn_left(),
+            facing,
+            facing.turn_right(),
+            facing.turn_back(),
+        ]
+        .into_iter()
+        .filter_map(|facing| {
+            let next = self.runner.next_cell(facing)?;
+            Some((facing, self.marks(self.runner.position, next)))
+        })
+        .collect()
+    }
+
+    // Chooses the next passage following Trémaux's rules, or None if every passage is marked twice.
+    fn choose(&self) -> Option<Facing> {
+        let passages = self.passages();
+        // The runner faces away from the passage it arrived through, unless it has not moved yet
+        let back = self.runner.facing.turn_back();
+        let arrival = passages
+            .iter()
+            .find(|(facing, _)| *facing == back && !self.marks.is_empty())
+            .copied();
+
+        if let Some((back, arrival_marks)) = arrival {
+            let mut others = passages.iter().filter(|(facing, _)| *facing != back);
+            if others.clone().all(|(_, marks)| *marks == 0) {
+                if let Some((facing, _)) = others.next() {
+                    return Some(*facing);
+                }
+            }
+            // Turn back on a dead end, or on a cell already visited through another passage
+            if arrival_marks == 1 {
+                return Some(back);
+            }
+        }
+
+        passages
+            .iter()
+            .filter(|(_, marks)| *marks < 2)
+            .min_by_key(|(_, marks)| *marks)
+            .map(|(facing, _)| *facing)
+    }
+
+    fn walk(&mut self) {
+        let Some(facing) = self.choose() else {
+            self.stuck = true;
+            return;
+        };
+        let from = self.runner.position;
+        if !self.runner.walk(facing) {
+            self.stuck = true;
+            return;
+        }
+        let to = self.runner.position;
+        *self
+            .marks
+            .entry(TremauxSearcher::passage(from, to))
+            .or_insert(0) += 1;
+    }
+}
+
+impl super::Searcher for TremauxSearcher {
+    // To get the current path, return the route walked so far, with its loops erased.
+    fn get_current_path(&self) -> Option<Path> {
+        (!self.stuck).then(|| self.runner.route())
+    }
+
+    // To get the considered nodes, return the cells the runner can move to.
+    fn get_considered_nodes(&self) -> Vec<MazeNode> {
+        self.runner.open_neighbours()
+    }
+
+    // To develop the next node, yield the cell the runner stands on, then walk one step.
+    fn develop_next_node(&mut self) -> Option<MazeNode> {
+        if self.done || self.stuck {
+            return None;
+        }
+        let node = self.runner.node()?;
+        if node == self.runner.maze().get_end() {
+            self.done = true;
+        } else {
+            self.walk();
+        }
+        Some(node)
+    }
+}
+
+impl Iterator for TremauxSearcher {
+    type Item = MazeNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.develop_next_node()
+    }
+}
@@ -0,0 +1,511 @@
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use crate::{
+    maze::{Coordinates, Maze},
+    tilemap::Tile,
+};
+
+use super::{path::Path, MazeNode, SearchFailure, Searcher};
+
+// The direction a runner inside the maze is facing. Runners only move up, left, down or right, even on mazes that
+// allow diagonal moves, as "following a wall" is only well defined along the axes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Facing {
+    Up,
+    Left,
+    Down,
+    Right,
+}
+
+// The hand a wall follower keeps on the wall
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+impl Facing {
+    pub fn turn_left(self) -> Facing {
+        match self {
+            Facing::Up => Facing::Left,
+            Facing::Left => Facing::Down,
+            Facing::Down => Facing::Right,
+            Facing::Right => Facing::Up,
+        }
+    }
+
+    pub fn turn_right(self) -> Facing {
+        match self {
+            Facing::Up => Facing::Right,
+            Facing::Right => Facing::Down,
+            Facing::Down => Facing::Left,
+            Facing::Left => Facing::Up,
+        }
+    }
+
+    pub fn turn_back(self) -> Facing {
+        self.turn_left().turn_left()
+    }
+
+    // The coordinates one step away in this direction, if they are not negative
+    pub fn step(self, coord: Coordinates) -> Option<Coordinates> {
+        let (dx, dy) = match self {
+            Facing::Up => (0, -1),
+            Facing::Left => (-1, 0),
+            Facing::Down => (0, 1),
+            Facing::Right => (1, 0),
+        };
+        Some((
+            coord.0.checked_add_signed(dx)?,
+            coord.1.checked_add_signed(dy)?,
+        ))
+    }
+
+    // The direction along the axis on which the target is the farthest away, vertical on ties
+    pub fn towards(from: Coordinates, to: Coordinates) -> Facing {
+        let (dx, dy) = (
+            to.0 as isize - from.0 as isize,
+            to.1 as isize - from.1 as isize,
+        );
+        match (dx.abs() > dy.abs(), dx > 0, dy > 0) {
+            (true, true, _) => Facing::Right,
+            (true, false, _) => Facing::Left,
+            (false, _, true) => Facing::Down,
+            (false, _, false) => Facing::Up,
+        }
+    }
+}
+
+// A runner walking inside the maze: its position, the way it is facing, and the route walked so far. The route is
+// kept with its loops erased (walking back to a cell of the route cuts the route back to it), so that it is always a
+// direct path from the start node to the runner.
+pub(super) struct Runner {
+    maze: Rc<Maze>,
+    pub(super) position: Coordinates,
+    pub(super) facing: Facing,
+    route: Vec<MazeNode>,
+    // The index of each cell of the route
+    indices: HashMap<Coordinates, usize>,
+    pub(super) steps: usize,
+}
+
+impl Runner {
+    pub(super) fn new(maze: Rc<Maze>) -> Runner {
+        let start_node = maze.get_start();
+        let position = start_node.get_coordinates();
+        let facing = Facing::towards(position, maze.get_end().get_coordinates());
+        Runner {
+            maze,
+            position,
+            facing,
+            route: vec![start_node],
+            indices: [(position, 0)].into(),
+            steps: 0,
+        }
+    }
+
+    pub(super) fn node(&self) -> Option<MazeNode> {
+        self.maze.get_node(self.position)
+    }
+
+    // The cell one step away in the given direction, if the runner can move there
+    pub(super) fn next_cell(&self, facing: Facing) -> Option<Coordinates> {
+        let next = facing.step(self.position)?;
+        self.maze.step_cost(self.position, next).map(|_| next)
+    }
+
+    pub(super) fn is_open(&self, facing: Facing) -> bool {
+        self.next_cell(facing).is_some()
+    }
+
+    // Whether the cell is a wall or out of the maze
+    pub(super) fn is_wall(&self, coord: Coordinates) -> bool {
+        self.maze
+            .tilemap()
+            .get(coord.0, coord.1)
+            .is_none_or(|tile| tile == Tile::Wall)
+    }
+
+    // Turns to the given direction and moves one step forward, if possible
+    pub(super) fn walk(&mut self, facing: Facing) -> bool {
+        self.facing = facing;
+        let Some(node) = self
+            .next_cell(facing)
+            .and_then(|next| self.maze.get_node(next))
+        else {
+            return false;
+        };
+        self.position = node.get_coordinates();
+        self.steps += 1;
+
+        if let Some(index) = self.indices.get(&self.position).copied() {
+            for erased in self.route.drain(index + 1..) {
+                self.indices.remove(&erased.get_coordinates());
+            }
+        } else {
+            self.indices.insert(self.position, self.route.len());
+            self.route.push(node);
+        }
+        true
+    }
+
+    pub(super) fn route(&self) -> Path {
+        self.route.iter().cloned().collect()
+    }
+
+    // The cells the runner can move to from its position
+    pub(super) fn open_neighbours(&self) -> Vec<MazeNode> {
+        [Facing::Up, Facing::Left, Facing::Down, Facing::Right]
+            .into_iter()
+            .filter_map(|facing| self.next_cell(facing))
+            .filter_map(|coord| self.maze.get_node(coord))
+            .collect()
+    }
+
+    pub(super) fn maze(&self) -> &Rc<Maze> {
+        &self.maze
+    }
+}
+
+// The Wall Follower searcher walks through the maze keeping one hand on a wall: at each step, it turns towards its
+// hand if the wall it follows turns that way, or else goes forward, or else turns away from its hand, or else turns
+// back. Starting away from any wall, it first walks straight ahead until it bumps into one, and turns away from its
+// hand to put that wall on its hand side.
+// This always finds the end node when it lies on the same wall as the start (as in mazes without loops), but it goes
+// around in circles forever when the end node is on an "island" of walls. Since the runner is deterministic, this is
+// detected as soon as it stands on the same cell facing the same way twice.
+pub struct WallFollowerSearcher {
+    runner: Runner,
+    hand: Hand,
+    touching_wall: bool,
+    // Every (position, facing) the runner stood in while following the wall
+    seen: HashSet<(Coordinates, Facing)>,
+    looping: bool,
+    stuck: bool,
+    done: bool,
+}
+
+impl WallFollowerSearcher {
+    pub fn new(maze: Rc<Maze>, hand: Hand) -> WallFollowerSearcher {
+        let runner = Runner::new(maze);
+        let touching_wall =
+            !runner.is_open(WallFollowerSearcher::towards_hand(hand, runner.facing));
+        WallFollowerSearcher {
+            runner,
+            hand,
+            touching_wall,
+            seen: HashSet::new(),
+            looping: false,
+            stuck: false,
+            done: false,
+        }
+    }
+
+    fn towards_hand(hand: Hand, facing: Facing) -> Facing {
+        match hand {
+            Hand::Left => facing.turn_left(),
+            Hand::Right => facing.turn_right(),
+        }
+    }
+
+    fn away_from_hand(hand: Hand, facing: Facing) -> Facing {
+        match hand {
+            Hand::Left => facing.turn_right(),
+            Hand::Right => facing.turn_left(),
+        }
+    }
+
+    pub fn facing(&self) -> Facing {
+        self.runner.facing
+    }
+
+    // The number of steps walked so far, including the ones erased from the route
+    pub fn steps(&self) -> usize {
+        self.runner.steps
+    }
+
+    // Walks one step, following the wall (or walking straight ahead until touching one)
+    fn walk(&mut self) {
+        let mut facing = self.runner.facing;
+        if !self.touching_wall {
+            if self.runner.walk(facing) {
+                return;
+            }
+            // Bumped into a wall: turn away from the hand, so that the wall is on the runner's hand
+            self.touching_wall = true;
+            facing = WallFollowerSearcher::away_from_hand(self.hand, facing);
+        }
+
+        // The wall goes on around a corner only if the cell behind the hand is a wall. Otherwise, the runner is in an
+        // open area, and turning towards its hand would only make it go around in circles.
+        let towards_hand = WallFollowerSearcher::towards_hand(self.hand, facing);
+        let wall_behind_hand = towards_hand
+            .step(self.runner.position)
+            .and_then(|beside| facing.turn_back().step(beside))
+            .is_none_or(|behind| self.runner.is_wall(behind));
+
+        let next_facing = [
+            wall_behind_hand.then_some(towards_hand),
+            Some(facing),
+            Some(WallFollowerSearcher::away_from_hand(self.hand, facing)),
+            Some(facing.turn_back()),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|facing| self.runner.is_open(*facing));
+
+        let Some(next_facing) = next_facing else {
+            self.stuck = true;
+            return;
+        };
+        self.runner.walk(next_facing);
+        if !self.seen.insert((self.runner.position, self.runner.facing)) {
+            self.looping = true;
+        }
+    }
+}
+
+impl super::Searcher for WallFollowerSearcher {
+    // To get the current path, return the route walked so far, with its loops erased.
+    fn get_current_path(&self) -> Option<Path> {
+        (!self.looping && !self.stuck).then(|| self.runner.route())
+    }
+
+    // To get the considered nodes, return the cells the runner can move to.
+    fn get_considered_nodes(&self) -> Vec<MazeNode> {
+        self.runner.open_neighbours()
+    }
+
+    // To develop the next node, yield the cell the runner stands on, then walk one step.
+    fn develop_next_node(&mut self) -> Option<MazeNode> {
+        if self.done || self.looping || self.stuck {
+            return None;
+        }
+        let node = self.runner.node()?;
+        if node == self.runner.maze().get_end() {
+            self.done = true;
+        } else {
+            self.walk();
+        }
+        Some(node)
+    }
+
+    fn get_failure(&self) -> Option<SearchFailure> {
+        self.looping.then_some(SearchFailure::Loop {
+            steps: self.runner.steps,
+        })
+    }
+}
+
+impl Iterator for WallFollowerSearcher {
+    type Item = MazeNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.develop_next_node()
+    }
+}
+
+// The Pledge searcher walks straight towards a preferred direction (the one leading towards the end node) until it
+// bumps into a wall, then follows the wall with its left hand while counting its turns (+1 left, -1 right). It leaves
+// the wall as soon as the count is back to 0, that is, when it faces the preferred direction again after going
+// around the obstacle. Unlike a plain wall follower, it escapes islands of walls, but it may still circle forever
+// when the end node lies behind them. As the turn count is part of the runner's state, a loop is detected when the
+// same state comes back, or when the count grows beyond what any non-looping walk could need.
+pub struct PledgeSearcher {
+    runner: Runner,
+    preferred: Facing,
+    following: bool,
+    turns: isize,
+    seen: HashSet<(Coordinates, Facing, isize, bool)>,
+    max_turns: isize,
+    looping: bool,
+    stuck: bool,
+    done: bool,
+}
+
+impl PledgeSearcher {
+    pub fn new(maze: Rc<Maze>) -> PledgeSearcher {
+        let max_turns = 4 * (maze.width() * maze.height()) as isize;
+        let runner = Runner::new(maze);
+        PledgeSearcher {
+            preferred: runner.facing,
+            runner,
+            following: false,
+            turns: 0,
+            seen: HashSet::new(),
+            max_turns,
+            looping: false,
+            stuck: false,
+            done: false,
+        }
+    }
+
+    pub fn facing(&self) -> Facing {
+        self.runner.facing
+    }
+
+    // The number of steps walked so far, including the ones erased from the route
+    pub fn steps(&self) -> usize {
+        self.runner.steps
+    }
+
+    // The number of turns taken while following the current wall, left turns counting as +1 and right turns as -1
+    pub fn turns(&self) -> isize {
+        self.turns
+    }
+
+    fn walk(&mut self) {
+        let facing = self.runner.facing;
+        let next = if self.following {
+            [
+                (facing.turn_left(), 1),
+                (facing, 0),
+                (facing.turn_right(), -1),
+                (facing.turn_back(), -2),
+            ]
+            .into_iter()
+            .find(|(facing, _)| self.runner.is_open(*facing))
+        } else {
+            // Walk towards the preferred direction, or else turn right (putting the wall on the left hand) until free
+            let preferred = self.preferred;
+            [
+                (preferred, 0),
+                (preferred.turn_right(), -1),
+                (preferred.turn_back(), -2),
+                (preferred.turn_left(), -3),
+            ]
+            .into_iter()
+            .find(|(facing, _)| self.runner.is_open(*facing))
+        };
+
+        let Some((next_facing, turns)) = next else {
+            self.stuck = true;
+            return;
+        };
+        self.following |= turns != 0;
+        self.turns += turns;
+        self.runner.walk(next_facing);
+        if self.turns == 0 {
+            self.following = false;
+        }
+
+        let state = (
+            self.runner.position,
+            self.runner.facing,
+            self.turns,
+            self.following,
+        );
+        if self.turns.abs() > self.max_turns || !self.seen.insert(state) {
+            self.looping = true;
+        }
+    }
+}
+
+impl super::Searcher for PledgeSearcher {
+    // To get the current path, return the route walked so far, with its loops erased.
+    fn get_current_path(&self) -> Option<Path> {
+        (!self.looping && !self.stuck).then(|| self.runner.route())
+    }
+
+    // To get the considered nodes, return the cells the runner can move to.
+    fn get_considered_nodes(&self) -> Vec<MazeNode> {
+        self.runner.open_neighbours()
+    }
+
+    // To develop the next node, yield the cell the runner stands on, then walk one step.
+    fn develop_next_node(&mut self) -> Option<MazeNode> {
+        if self.done || self.looping || self.stuck {
+            return None;
+        }
+        let node = self.runner.node()?;
+        if node == self.runner.maze().get_end() {
+            self.done = true;
+        } else {
+            self.walk();
+        }
+        Some(node)
+    }
+
+    fn get_failure(&self) -> Option<SearchFailure> {
+        self.looping.then_some(SearchFailure::Loop {
+            steps: self.runner.steps,
+        })
+    }
+}
+
+impl Iterator for PledgeSearcher {
+    type Item = MazeNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.develop_next_node()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, rc::Rc};
+
+    use super::{Hand, PledgeSearcher, WallFollowerSearcher};
+    use crate::maze::Maze;
+    use crate::search::Searcher;
+    use crate::test_utils::{load_example, solve, EXAMPLES};
+    use crate::tilemap::TileMap;
+
+    // A room with no inner walls, where a runner turning towards its hand whenever it can goes around in circles
+    const OPEN_ROOM: &str = "1111111\n1000001\n1020001\n1000001\n1000031\n1111111";
+
+    fn searchers(maze: &Rc<Maze>) -> [(&'static str, Box<dyn Searcher>); 3] {
+        [
+            (
+                "left",
+                Box::new(WallFollowerSearcher::new(maze.clone(), Hand::Left)),
+            ),
+            (
+                "right",
+                Box::new(WallFollowerSearcher::new(maze.clone(), Hand::Right)),
+            ),
+            ("pledge", Box::new(PledgeSearcher::new(maze.clone()))),
+        ]
+    }
+
+    // Asserts that every searcher walks a valid path from the start node to the end node
+    fn assert_reaches_end(maze: &Rc<Maze>, name: &str) {
+        for (searcher_name, mut searcher) in searchers(maze) {
+            let path = solve(searcher.as_mut());
+            assert_eq!(
+                path.as_ref().and_then(|path| path.first().cloned()),
+                Some(maze.get_start()),
+                "{searcher_name} on {name}"
+            );
+            assert_eq!(
+                path.as_ref().and_then(|path| path.last().cloned()),
+                Some(maze.get_end()),
+                "{searcher_name} on {name}"
+            );
+            assert!(
+                path.and_then(|path| path.cost()).is_some(),
+                "{searcher_name} on {name}"
+            );
+            assert_eq!(searcher.get_failure(), None, "{searcher_name} on {name}");
+        }
+    }
+
+    #[test]
+    fn reaches_the_end_in_an_open_room() -> Result<(), Box<dyn Error>> {
+        let maze = Rc::new(Maze::try_from(TileMap::try_from(OPEN_ROOM.to_string())?)?);
+        assert_reaches_end(&maze, "the open room");
+        Ok(())
+    }
+
+    #[test]
+    fn reaches_the_end_on_examples() -> Result<(), Box<dyn Error>> {
+        for example in EXAMPLES {
+            let maze = Rc::new(Maze::try_from(load_example(example)?)?);
+            assert_reaches_end(&maze, example);
+        }
+        Ok(())
+    }
+}
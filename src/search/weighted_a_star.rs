@@ -0,0 +1,65 @@
+use std::rc::Rc;
+
+use crate::maze::Maze;
+
+use super::{
+    a_star::{AStarSearcher, HeuristicFn},
+    path::Path,
+    MazeNode, SearchMode, Searcher,
+};
+
+// Weighted A* orders paths by cost + epsilon * heuristic. With epsilon above 1, paths close to the end node are
+// favoured over cheap ones, so fewer nodes are usually developed, but the path found may cost up to epsilon times the
+// optimal one (as long as the heuristic is admissible).
+// The Weighted A* searcher wraps an A* searcher whose heuristic is scaled by epsilon.
+pub struct WeightedAStarSearcher<F>
+where
+    F: HeuristicFn,
+{
+    searcher: AStarSearcher<F>,
+    epsilon: f64,
+}
+
+impl<F: HeuristicFn> WeightedAStarSearcher<F> {
+    pub fn new(maze: Rc<Maze>, heuristic: Box<F>, epsilon: f64) -> WeightedAStarSearcher<F> {
+        WeightedAStarSearcher::with_mode(maze, heuristic, epsilon, SearchMode::default())
+    }
+
+    pub fn with_mode(
+        maze: Rc<Maze>,
+        heuristic: Box<F>,
+        epsilon: f64,
+        mode: SearchMode,
+    ) -> WeightedAStarSearcher<F> {
+        WeightedAStarSearcher {
+            searcher: AStarSearcher::with_epsilon(maze, heuristic, mode, epsilon),
+            epsilon,
+        }
+    }
+}
+
+impl<F: HeuristicFn> super::Searcher for WeightedAStarSearcher<F> {
+    fn get_current_path(&self) -> Option<Path> {
+        self.searcher.get_current_path()
+    }
+
+    fn get_considered_nodes(&self) -> Vec<MazeNode> {
+        self.searcher.get_considered_nodes()
+    }
+
+    fn develop_next_node(&mut self) -> Option<MazeNode> {
+        self.searcher.develop_next_node()
+    }
+
+    fn get_suboptimality_bound(&self) -> Option<f64> {
+        Some(self.epsilon.max(1.))
+    }
+}
+
+impl<F: HeuristicFn> Iterator for WeightedAStarSearcher<F> {
+    type Item = MazeNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.develop_next_node()
+    }
+}
@@ -0,0 +1,37 @@
+// Helpers shared by the tests of the different modules
+
+use std::{error::Error, fs, path::PathBuf};
+
+use crate::search::{path::Path, Searcher};
+use crate::tilemap::{Tile, TileMap};
+
+// The directories of the bundled examples, from the smallest to the largest
+pub(crate) const EXAMPLES: [&str; 4] = ["10x10", "50x50", "100x100", "200x200"];
+
+// The path of a bundled example, with the given extension ("txt" or "npy")
+pub(crate) fn example_path(example: &str, extension: &str) -> PathBuf {
+    [
+        env!("CARGO_MANIFEST_DIR"),
+        example,
+        &format!("exemplo_labirinto.{extension}"),
+    ]
+    .iter()
+    .collect()
+}
+
+// Reads a bundled example from its text file
+pub(crate) fn load_example(example: &str) -> Result<TileMap, Box<dyn Error>> {
+    Ok(TileMap::try_from(fs::read_to_string(example_path(
+        example, "txt",
+    ))?)?)
+}
+
+// Runs a searcher until it yields the end node, and returns the path it found, or None if it gave up
+pub(crate) fn solve(searcher: &mut dyn Searcher) -> Option<Path> {
+    loop {
+        let path = searcher.get_current_path();
+        if searcher.next()?.get_tile() == Tile::End {
+            return path;
+        }
+    }
+}
@@ -1,9 +1,17 @@
+use std::fmt::{Display, Formatter};
+
+use crate::maze::Cost;
+use crate::npy::{self, NpyArray, NpyError};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Tile {
     Empty,
     Wall,
     Start,
     End,
+    // Weighted terrain, which can be walked through at a higher cost than empty tiles
+    Mud,
+    Water,
 }
 
 #[derive(PartialEq, Eq, Clone, Copy)]
@@ -11,86 +19,287 @@ pub enum EmptyTileState {
     Focused,
     Visited,
     Considering,
+    // Part of the best solution published so far by an anytime searcher
+    Solution,
+    // Filled in by a solver that prunes the maze
+    Filled,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct TileMap(Vec<Vec<Tile>>);
 pub struct TileMapIter(TileMap, usize);
 
-#[allow(dead_code)]
-struct TileMapBuildError;
+// Key-value pairs saved along with a tilemap, such as how it was generated. They are written as `# key: value`
+// comment lines before the rows of the text format, and as a `# key: value; key: value` comment after the header
+// dict of NPY files. Both formats ignore the metadata when reading the tilemap itself.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Metadata(Vec<(String, String)>);
+
+// Errors that can happen while building a TileMap, either from text, from a NPY array or from raw tiles
+#[derive(Debug, Clone, PartialEq)]
+pub enum TileMapError {
+    // The tilemap has no rows (or no columns)
+    Empty,
+    // A character other than a tile digit (0 to 5) or whitespace was found (line and column are 1-based)
+    InvalidCharacter {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    // A NPY array contains a value that does not encode any tile (row and column are 0-based)
+    InvalidValue {
+        row: usize,
+        column: usize,
+        found: i64,
+    },
+    // A row (0-based) does not have the same length as the first row
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    // The number of Start tiles found, which must be exactly one
+    StartCount(usize),
+    // The number of End tiles found, which must be exactly one
+    EndCount(usize),
+    // The file could not be read as a NPY array
+    Npy(NpyError),
+}
+
+impl Display for TileMapError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            TileMapError::Empty => write!(f, "TileMap cannot be empty"),
+            TileMapError::InvalidCharacter {
+                line,
+                column,
+                found,
+            } => write!(
+                f,
+                "Expected values 0, 1, 2, 3, 4, or 5. Found {found:?} at line {line}, column {column}"
+            ),
+            TileMapError::InvalidValue { row, column, found } => write!(
+                f,
+                "Expected values 0, 1, 2, 3, 4, or 5. Found {found} at row {row}, column {column}"
+            ),
+            TileMapError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Row {row} has length {found}, but the first row has length {expected}"
+            ),
+            TileMapError::StartCount(count) => {
+                write!(f, "TileMap must have exactly one Start tile. Found {count}")
+            }
+            TileMapError::EndCount(count) => {
+                write!(f, "TileMap must have exactly one End tile. Found {count}")
+            }
+            TileMapError::Npy(error) => write!(f, "Invalid NPY file: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for TileMapError {}
+
+impl From<NpyError> for TileMapError {
+    fn from(value: NpyError) -> Self {
+        TileMapError::Npy(value)
+    }
+}
 
 impl TryFrom<Vec<Vec<Tile>>> for TileMap {
-    type Error = &'static str;
+    type Error = TileMapError;
 
     fn try_from(value: Vec<Vec<Tile>>) -> Result<Self, Self::Error> {
-        let Some(first_row) = value.first() else {
-            return Err("TileMap cannot be empty");
+        let Some(first_row) = value.first().filter(|row| !row.is_empty()) else {
+            return Err(TileMapError::Empty);
         };
 
         let first_len = first_row.len();
 
-        if value
+        if let Some((row, found)) = value
             .iter()
             .map(|vec| vec.len())
-            .any(|size| size != first_len)
+            .enumerate()
+            .find(|(_, size)| *size != first_len)
         {
-            Err("Rows have different lengths")
-        } else if value
-            .iter()
-            .flatten()
-            .copied()
-            .filter(|e| *e == Tile::Start)
-            .count()
-            != 1usize
-        {
-            Err("TileMap must have exactly one Start tile")
-        } else if value
-            .iter()
-            .flatten()
-            .copied()
-            .filter(|e| *e == Tile::End)
-            .count()
-            != 1usize
-        {
-            Err("TileMap must have exactly one End tile")
-        } else {
-            Ok(TileMap(value))
+            return Err(TileMapError::RaggedRow {
+                row,
+                expected: first_len,
+                found,
+            });
+        }
+
+        let count_tiles = |tile: Tile| value.iter().flatten().filter(|e| **e == tile).count();
+
+        match (count_tiles(Tile::Start), count_tiles(Tile::End)) {
+            (1, 1) => Ok(TileMap(value)),
+            (1, end_count) => Err(TileMapError::EndCount(end_count)),
+            (start_count, _) => Err(TileMapError::StartCount(start_count)),
         }
     }
 }
 
 impl TryFrom<String> for TileMap {
-    type Error = &'static str;
+    type Error = TileMapError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        let chars: Vec<Vec<char>> = value
+        let tiles: Vec<Vec<Tile>> = value
             .split('\n')
-            .map(|line| line.chars().collect::<Vec<char>>())
-            .collect();
+            .enumerate()
+            // Comment lines hold the metadata, and are not part of the map
+            .filter(|(_, line)| !line.trim_start().starts_with('#'))
+            .map(|(line_idx, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|(_, char)| !matches!(char, ' ' | '\t' | '\r'))
+                    .map(|(column_idx, char)| {
+                        Tile::from_char(char).ok_or(TileMapError::InvalidCharacter {
+                            line: line_idx + 1,
+                            column: column_idx + 1,
+                            found: char,
+                        })
+                    })
+                    .collect::<Result<Vec<Tile>, _>>()
+            })
+            .filter(|row| !matches!(row, Ok(tiles) if tiles.is_empty()))
+            .collect::<Result<Vec<Vec<Tile>>, _>>()?;
 
-        let tiles: Vec<Vec<Tile>> = chars
-            .iter()
+        TryInto::<TileMap>::try_into(tiles)
+    }
+}
+
+// Tiles are encoded with the same values in the text format (as digits) and in NPY arrays (as integers)
+impl Tile {
+    pub fn from_char(value: char) -> Option<Tile> {
+        value
+            .to_digit(10)
+            .and_then(|digit| Tile::from_value(digit as i64))
+    }
+
+    pub fn from_value(value: i64) -> Option<Tile> {
+        match value {
+            0 => Some(Tile::Empty),
+            1 => Some(Tile::Wall),
+            2 => Some(Tile::Start),
+            3 => Some(Tile::End),
+            4 => Some(Tile::Mud),
+            5 => Some(Tile::Water),
+            _ => None,
+        }
+    }
+
+    // The multiplier applied to the cost of stepping onto the tile, or None if the tile cannot be walked through
+    pub fn weight(self) -> Option<Cost> {
+        match self {
+            Tile::Empty | Tile::Start | Tile::End => Some(1),
+            Tile::Mud => Some(3),
+            Tile::Water => Some(5),
+            Tile::Wall => None,
+        }
+    }
+}
+
+impl From<Tile> for i64 {
+    fn from(value: Tile) -> Self {
+        match value {
+            Tile::Empty => 0,
+            Tile::Wall => 1,
+            Tile::Start => 2,
+            Tile::End => 3,
+            Tile::Mud => 4,
+            Tile::Water => 5,
+        }
+    }
+}
+
+impl From<Tile> for char {
+    fn from(value: Tile) -> Self {
+        match value {
+            Tile::Empty => '0',
+            Tile::Wall => '1',
+            Tile::Start => '2',
+            Tile::End => '3',
+            Tile::Mud => '4',
+            Tile::Water => '5',
+        }
+    }
+}
+
+impl TryFrom<NpyArray> for TileMap {
+    type Error = TileMapError;
+
+    fn try_from(value: NpyArray) -> Result<Self, Self::Error> {
+        let tiles = (0..value.rows)
             .map(|row| {
-                row.iter()
-                    .filter_map(|char| match char {
-                        '0' => Some(Tile::Empty),
-                        '1' => Some(Tile::Wall),
-                        '2' => Some(Tile::Start),
-                        '3' => Some(Tile::End),
-                        ' ' | '\t' | '\n' | '\r' => None,
-                        _ => panic!("Expected values 0, 1, 2, or 3. Found {:?}", char),
+                (0..value.cols)
+                    .map(|column| {
+                        let found = value.get(row, column).ok_or(NpyError::PayloadLength {
+                            expected: value.rows * value.cols,
+                            found: value.data.len(),
+                        })?;
+                        Tile::from_value(found).ok_or(TileMapError::InvalidValue {
+                            row,
+                            column,
+                            found,
+                        })
                     })
-                    .collect::<Vec<Tile>>()
+                    .collect::<Result<Vec<Tile>, _>>()
             })
-            .filter(|row| !row.is_empty())
-            .collect::<Vec<Vec<Tile>>>();
+            .collect::<Result<Vec<Vec<Tile>>, _>>()?;
 
         TryInto::<TileMap>::try_into(tiles)
     }
 }
 
+impl From<&TileMap> for NpyArray {
+    fn from(value: &TileMap) -> Self {
+        NpyArray {
+            rows: value.height(),
+            cols: value.width(),
+            data: value.0.iter().flatten().copied().map(i64::from).collect(),
+        }
+    }
+}
+
+// The text format has one line per row, with one digit per tile and no separators
+impl Display for TileMap {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        for row in &self.0 {
+            let line: String = row.iter().copied().map(char::from).collect();
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
 impl TileMap {
+    // Parses the raw contents of a .npy file, where each row of the array is a row of the maze
+    pub fn from_npy(bytes: &[u8]) -> Result<TileMap, TileMapError> {
+        NpyArray::try_from(bytes)?.try_into()
+    }
+
+    // Serializes the tilemap as the raw contents of a .npy file, readable by `TileMap::from_npy`
+    pub fn to_npy(&self) -> Vec<u8> {
+        NpyArray::from(self).to_bytes()
+    }
+
+    // Serializes the tilemap in the text format, preceded by the metadata as comment lines
+    pub fn to_text_with_metadata(&self, metadata: &Metadata) -> String {
+        let comments: String = metadata
+            .iter()
+            .map(|(key, value)| format!("# {key}: {value}\n"))
+            .collect();
+        format!("{comments}{self}")
+    }
+
+    // Serializes the tilemap like `to_npy`, with the metadata in the header
+    pub fn to_npy_with_metadata(&self, metadata: &Metadata) -> Vec<u8> {
+        NpyArray::from(self).to_bytes_with_comment(&metadata.to_npy_comment())
+    }
+
     pub fn width(&self) -> usize {
         #[allow(clippy::expect_used)]
         return self.0.first().expect("TileMap should never be empty").len();
@@ -103,6 +312,84 @@ impl TileMap {
     pub fn get(&self, x: usize, y: usize) -> Option<Tile> {
         self.0.get(y).and_then(|row| row.get(x)).copied()
     }
+
+    // Replaces the tile at the given coordinates, and returns the previous one.
+    // Callers are responsible for keeping exactly one Start and one End tile.
+    pub(crate) fn set(&mut self, x: usize, y: usize, tile: Tile) -> Option<Tile> {
+        let cell = self.0.get_mut(y)?.get_mut(x)?;
+        Some(std::mem::replace(cell, tile))
+    }
+}
+
+impl Metadata {
+    pub fn new() -> Metadata {
+        Metadata(Vec::new())
+    }
+
+    // Sets the value of a key, keeping the order in which the keys were first inserted. The separators of the
+    // formats (':' in keys, ';' in values) and control characters are dropped, so that the pairs read back the same.
+    pub fn insert(&mut self, key: &str, value: &str) {
+        let key: String = key
+            .chars()
+            .filter(|c| !c.is_control() && !matches!(c, ':' | ';' | '#'))
+            .collect();
+        let value: String = value
+            .chars()
+            .filter(|c| !c.is_control() && *c != ';')
+            .collect();
+        let (key, value) = (key.trim().to_string(), value.trim().to_string());
+
+        match self.0.iter_mut().find(|(existing, _)| *existing == key) {
+            Some((_, existing)) => *existing = value,
+            None => self.0.push((key, value)),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // Reads the metadata from the comment lines of the text format
+    pub fn from_text(text: &str) -> Metadata {
+        Metadata::from_pairs(
+            text.lines()
+                .filter_map(|line| line.trim_start().strip_prefix('#')),
+        )
+    }
+
+    // Reads the metadata from the header of a .npy file. Files without metadata have empty metadata.
+    pub fn from_npy(bytes: &[u8]) -> Result<Metadata, NpyError> {
+        let comment = npy::header_comment(bytes)?.unwrap_or_default();
+        Ok(Metadata::from_pairs(comment.split(';')))
+    }
+
+    fn from_pairs<'a>(pairs: impl Iterator<Item = &'a str>) -> Metadata {
+        let mut metadata = Metadata::new();
+        pairs
+            .filter_map(|pair| pair.split_once(':'))
+            .for_each(|(key, value)| metadata.insert(key, value));
+        metadata
+    }
+
+    fn to_npy_comment(&self) -> String {
+        self.iter()
+            .map(|(key, value)| format!("{key}: {value}"))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
 }
 
 impl Iterator for TileMapIter {
@@ -130,3 +417,42 @@ impl<'a> From<&'a TileMap> for TileMapIter {
         TileMapIter(value.clone(), 0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, fs};
+
+    use super::TileMap;
+    use crate::test_utils::{example_path, load_example, EXAMPLES};
+
+    #[test]
+    fn text_round_trip() -> Result<(), Box<dyn Error>> {
+        for example in EXAMPLES {
+            let tilemap = load_example(example)?;
+            assert_eq!(
+                TileMap::try_from(tilemap.to_string())?,
+                tilemap,
+                "{example}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn npy_round_trip() -> Result<(), Box<dyn Error>> {
+        for example in EXAMPLES {
+            let tilemap = load_example(example)?;
+            assert_eq!(TileMap::from_npy(&tilemap.to_npy())?, tilemap, "{example}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn bundled_npy_matches_text() -> Result<(), Box<dyn Error>> {
+        for example in EXAMPLES {
+            let npy = TileMap::from_npy(&fs::read(example_path(example, "npy"))?)?;
+            assert_eq!(npy, load_example(example)?, "{example}");
+        }
+        Ok(())
+    }
+}